@@ -0,0 +1,288 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use crate::{
+    range_map::{RangeMap, RangeMapError},
+    unsigned::Unsigned,
+};
+
+#[derive(Debug)]
+pub enum AlmanacError {
+    InvalidHeader(String),
+    InvalidSeed(String),
+    SeedRangeOverflow { start: String, len: String },
+    Map(RangeMapError),
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::InvalidHeader(header) => write!(f, "Invalid map header: {header}"),
+            AlmanacError::InvalidSeed(seed) => write!(f, "Invalid seed: {seed}"),
+            AlmanacError::SeedRangeOverflow { start, len } => write!(
+                f,
+                "Seed range starting at {start} with length {len} does not fit in the integer type"
+            ),
+            AlmanacError::Map(err) => err.fmt(f),
+        }
+    }
+}
+
+impl From<RangeMapError> for AlmanacError {
+    fn from(err: RangeMapError) -> Self {
+        AlmanacError::Map(err)
+    }
+}
+
+#[derive(Debug)]
+pub struct CategoryMap<T> {
+    pub source: String,
+    pub target: String,
+    pub map: RangeMap<T>,
+}
+
+impl<T: Unsigned> TryFrom<&str> for CategoryMap<T> {
+    type Error = AlmanacError;
+
+    fn try_from(section: &str) -> Result<Self, Self::Error> {
+        // Header looks like "seed-to-soil map:"
+        let header = section.lines().next().unwrap();
+        let (source, target) = header
+            .trim_end_matches(" map:")
+            .split_once("-to-")
+            .ok_or_else(|| AlmanacError::InvalidHeader(header.to_owned()))?;
+
+        Ok(Self {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            map: section.try_into()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Almanac<T> {
+    pub seeds: Vec<T>,
+    pub maps: Vec<CategoryMap<T>>,
+}
+
+impl<T: Unsigned> Almanac<T> {
+    /// Reads the seeds as the `start length` pairs used by part 2, turned into `(start, end)`.
+    pub fn seed_ranges(&self) -> Result<Vec<(T, T)>, AlmanacError> {
+        self.seeds
+            .chunks(2)
+            .map(|chunk| match *chunk {
+                [start, len] => start
+                    .checked_add(len)
+                    .map(|end| (start, end))
+                    .ok_or_else(|| AlmanacError::SeedRangeOverflow {
+                        start: start.to_string(),
+                        len: len.to_string(),
+                    }),
+                _ => Err(AlmanacError::InvalidSeed(format!("{:?}", chunk))),
+            })
+            .collect()
+    }
+
+    /// Finds the shortest chain of maps leading from `source` to `target`.
+    pub fn path(&self, source: &str, target: &str) -> Option<Vec<&CategoryMap<T>>> {
+        let mut came_from: HashMap<&str, &CategoryMap<T>> = HashMap::new();
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == target {
+                let mut path = vec![];
+                let mut current = target;
+
+                while current != source {
+                    let map = came_from[current];
+                    path.push(map);
+                    current = &map.source;
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for map in self.maps.iter().filter(|map| map.source == category) {
+                if map.target != source && !came_from.contains_key(map.target.as_str()) {
+                    came_from.insert(&map.target, map);
+                    queue.push_back(&map.target);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Composes every map on the way from `source` to `target` into one.
+    pub fn resolve(&self, source: &str, target: &str) -> Option<RangeMap<T>> {
+        let path = self.path(source, target)?;
+
+        Some(
+            path.into_iter()
+                .fold(RangeMap::default(), |composed, category_map| {
+                    composed.compose(&category_map.map)
+                }),
+        )
+    }
+
+    /// Walks the locations upwards and returns the first one that traces back into one of the
+    /// given seed ranges.
+    pub fn lowest_location(&self, seed_ranges: &[(T, T)]) -> Option<T> {
+        let seed_to_location = self.resolve("seed", "location")?;
+
+        for window in seed_to_location.dest_boundaries().windows(2) {
+            let (start, end) = (window[0], window[1]);
+
+            let lowest = seed_to_location
+                .preimage_range(start, end)
+                .into_iter()
+                .flat_map(|piece| {
+                    seed_ranges
+                        .iter()
+                        .filter_map(move |&(seed_start, seed_end)| {
+                            let overlap_start = piece.source_start.max(seed_start);
+                            let overlap_end = piece.source_end().min(seed_end);
+
+                            (overlap_start < overlap_end)
+                                .then(|| piece.dest_start + (overlap_start - piece.source_start))
+                        })
+                })
+                .min();
+
+            if lowest.is_some() {
+                return lowest;
+            }
+        }
+
+        None
+    }
+
+    /// All the seeds that end up at `location`.
+    pub fn seeds_at(&self, location: T) -> Option<Vec<T>> {
+        Some(self.resolve("seed", "location")?.preimage(location))
+    }
+}
+
+impl<T: Unsigned> TryFrom<String> for Almanac<T> {
+    type Error = AlmanacError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut sections = value.split("\n\n");
+
+        let seeds: Vec<T> = sections
+            .next()
+            .unwrap()
+            .split(": ")
+            .last() // Remove "seeds: "
+            .unwrap()
+            .split_ascii_whitespace()
+            .map(|num_str| {
+                num_str
+                    .parse::<T>()
+                    .map_err(|_| AlmanacError::InvalidSeed(num_str.to_owned()))
+            }) // Parse the list of numbers into vec
+            .collect::<Result<_, _>>()?;
+
+        let maps = sections
+            .filter(|section| !section.trim().is_empty())
+            .map(|section| section.try_into())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { seeds, maps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_map::tests::{random_map_lines, Rng};
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn example() {
+        let almanac: Almanac<u64> = EXAMPLE.to_owned().try_into().unwrap();
+        let seed_to_location = almanac.resolve("seed", "location").unwrap();
+
+        let locations: Vec<u64> = almanac
+            .seeds
+            .iter()
+            .map(|seed| seed_to_location.get(*seed))
+            .collect();
+        assert_eq!(locations, [82, 43, 86, 35]);
+
+        let seed_ranges = almanac.seed_ranges().unwrap();
+        assert_eq!(almanac.lowest_location(&seed_ranges), Some(46));
+        assert!(almanac.seeds_at(46).unwrap().contains(&82));
+    }
+
+    #[test]
+    fn lowest_location_matches_brute_force() {
+        let mut rng = Rng(0x1234_5678_9abc_def1);
+
+        for _ in 0..300 {
+            let seeds: Vec<String> = (0..2)
+                .flat_map(|_| {
+                    let start = rng.below(250);
+                    [start, 1 + rng.below(255 - start)]
+                })
+                .map(|value| value.to_string())
+                .collect();
+
+            let mut input = format!("seeds: {}\n", seeds.join(" "));
+            // Out of order, so the path has to be found rather than read top to bottom
+            for (source, target) in [("soil", "location"), ("seed", "soil")] {
+                let lines = random_map_lines(&mut rng).join("\n");
+                input += &format!("\n{source}-to-{target} map:\n{lines}\n");
+            }
+
+            let almanac: Almanac<u8> = input.clone().try_into().unwrap();
+            let seed_ranges = almanac.seed_ranges().unwrap();
+            let [seed_to_soil, soil_to_location] = [1, 0].map(|i| &almanac.maps[i].map);
+
+            let expected = seed_ranges
+                .iter()
+                .flat_map(|&(start, end)| start..end)
+                .map(|seed| soil_to_location.get(seed_to_soil.get(seed)))
+                .min();
+            assert_eq!(almanac.lowest_location(&seed_ranges), expected, "{input}");
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+
+use crate::unsigned::Unsigned;
+
+/// One line of a map. Construction guarantees that both `source_start + len` and
+/// `dest_start + len` fit in `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeMapEntry<T> {
+    pub source_start: T,
+    pub dest_start: T,
+    pub len: T,
+}
+
+impl<T: Unsigned> RangeMapEntry<T> {
+    pub fn source_end(&self) -> T {
+        self.source_start + self.len
+    }
+
+    pub fn dest_end(&self) -> T {
+        self.dest_start + self.len
+    }
+
+    fn is_identity(&self) -> bool {
+        self.source_start == self.dest_start
+    }
+}
+
+#[derive(Debug)]
+pub enum RangeMapError {
+    Overlap { first: String, second: String },
+    Overflow { line: String },
+    Parse { line: String },
+}
+
+impl fmt::Display for RangeMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeMapError::Overlap { first, second } => {
+                write!(f, "Source ranges overlap: \"{first}\" and \"{second}\"")
+            }
+            RangeMapError::Overflow { line } => {
+                write!(f, "Range does not fit in the integer type: \"{line}\"")
+            }
+            RangeMapError::Parse { line } => write!(f, "Invalid map line: \"{line}\""),
+        }
+    }
+}
+
+/// Entries sorted by `source_start`, with no two source ranges overlapping.
+///
+/// Ranges passed in and out are half open `(start, end)` pairs, so the whole domain is
+/// `T::ZERO..T::MAX` and `T::MAX` itself always maps onto itself.
+#[derive(Debug, Clone, Default)]
+pub struct RangeMap<T>(Vec<RangeMapEntry<T>>);
+
+impl<T: Unsigned> RangeMap<T> {
+    pub fn entries(&self) -> &[RangeMapEntry<T>] {
+        &self.0
+    }
+
+    pub fn get(&self, value: T) -> T {
+        // Index of the first entry that starts after value, the one before it is the only candidate
+        let idx = self.0.partition_point(|entry| entry.source_start <= value);
+
+        match idx.checked_sub(1).map(|idx| &self.0[idx]) {
+            Some(entry) if value < entry.source_end() => {
+                entry.dest_start + (value - entry.source_start)
+            }
+            _ => value,
+        }
+    }
+
+    /// Splits `start..end` into the pieces that move together, including the ones that fall
+    /// outside of every entry and map onto themselves.
+    pub fn split_range(&self, start: T, end: T) -> Vec<RangeMapEntry<T>> {
+        let mut pieces = vec![];
+        let mut current = start;
+
+        // Skip the entries that end before the range does, starting from the one containing start
+        let first = self.0.partition_point(|entry| entry.source_end() <= start);
+
+        for entry in self.0[first..].iter() {
+            if current >= end {
+                break;
+            }
+
+            if current < entry.source_start {
+                let gap_end = entry.source_start.min(end);
+                pieces.push(RangeMapEntry {
+                    source_start: current,
+                    dest_start: current,
+                    len: gap_end - current,
+                });
+                current = gap_end;
+            }
+
+            let overlap_end = entry.source_end().min(end);
+            if current < overlap_end {
+                pieces.push(RangeMapEntry {
+                    source_start: current,
+                    dest_start: entry.dest_start + (current - entry.source_start),
+                    len: overlap_end - current,
+                });
+                current = overlap_end;
+            }
+        }
+
+        if current < end {
+            pieces.push(RangeMapEntry {
+                source_start: current,
+                dest_start: current,
+                len: end - current,
+            });
+        }
+
+        pieces
+    }
+
+    /// Builds a single map equivalent to applying `self` and then `next`.
+    pub fn compose(&self, next: &RangeMap<T>) -> RangeMap<T> {
+        let entries = self
+            .split_range(T::ZERO, T::MAX)
+            .into_iter()
+            .flat_map(|piece| {
+                next.split_range(piece.dest_start, piece.dest_end())
+                    .into_iter()
+                    .map(move |image| RangeMapEntry {
+                        source_start: piece.source_start + (image.source_start - piece.dest_start),
+                        dest_start: image.dest_start,
+                        len: image.len,
+                    })
+            })
+            .filter(|entry| !entry.is_identity())
+            .collect();
+
+        RangeMap(entries)
+    }
+
+    /// Returns the pieces of the source space that land inside `start..end`, ordered by where
+    /// they land.
+    pub fn preimage_range(&self, start: T, end: T) -> Vec<RangeMapEntry<T>> {
+        let mut pieces: Vec<RangeMapEntry<T>> = self
+            .split_range(T::ZERO, T::MAX)
+            .into_iter()
+            .filter_map(|piece| {
+                let overlap_start = piece.dest_start.max(start);
+                let overlap_end = piece.dest_end().min(end);
+
+                (overlap_start < overlap_end).then(|| RangeMapEntry {
+                    source_start: piece.source_start + (overlap_start - piece.dest_start),
+                    dest_start: overlap_start,
+                    len: overlap_end - overlap_start,
+                })
+            })
+            .collect();
+
+        pieces.sort_by_key(|piece| (piece.dest_start, piece.source_start));
+        pieces
+    }
+
+    /// Every value that `get` maps onto `value`.
+    pub fn preimage(&self, value: T) -> Vec<T> {
+        match value.checked_add(T::ONE) {
+            Some(end) => self
+                .preimage_range(value, end)
+                .into_iter()
+                .map(|piece| piece.source_start)
+                .collect(),
+            // No entry can reach T::MAX, so only T::MAX itself ends up there
+            None => vec![value],
+        }
+    }
+
+    /// All the points where a different piece of the map starts or stops landing.
+    pub fn dest_boundaries(&self) -> Vec<T> {
+        let mut boundaries: Vec<T> = self
+            .split_range(T::ZERO, T::MAX)
+            .into_iter()
+            .flat_map(|piece| [piece.dest_start, piece.dest_end()])
+            .collect();
+
+        boundaries.sort();
+        boundaries.dedup();
+        boundaries
+    }
+}
+
+impl<T: Unsigned> TryFrom<&str> for RangeMap<T> {
+    type Error = RangeMapError;
+
+    fn try_from(map_str: &str) -> Result<Self, Self::Error> {
+        let mut map_lines = map_str.lines();
+        map_lines.next().unwrap(); // Remove first line which is the name of the map
+
+        let mut entries = map_lines
+            .map(|line| {
+                let invalid = || RangeMapError::Parse {
+                    line: line.to_owned(),
+                };
+
+                let numbers: Vec<T> = line
+                    .split_ascii_whitespace()
+                    .map(|num| num.parse::<T>().map_err(|_| invalid()))
+                    .collect::<Result<_, _>>()?;
+
+                let [dest_start, source_start, len] = numbers[..] else {
+                    return Err(invalid());
+                };
+
+                if source_start.checked_add(len).is_none() || dest_start.checked_add(len).is_none()
+                {
+                    return Err(RangeMapError::Overflow {
+                        line: line.to_owned(),
+                    });
+                }
+
+                Ok((
+                    RangeMapEntry {
+                        source_start,
+                        dest_start,
+                        len,
+                    },
+                    line,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        entries.sort_by_key(|(entry, _)| entry.source_start);
+
+        // Once sorted, any overlap has to show up between neighbours
+        for window in entries.windows(2) {
+            let (previous, previous_line) = window[0];
+            let (next, next_line) = window[1];
+
+            if next.source_start < previous.source_end() {
+                return Err(RangeMapError::Overlap {
+                    first: previous_line.to_owned(),
+                    second: next_line.to_owned(),
+                });
+            }
+        }
+
+        Ok(RangeMap(
+            entries.into_iter().map(|(entry, _)| entry).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A small xorshift generator, so random maps don't need a dependency.
+    pub struct Rng(pub u64);
+
+    impl Rng {
+        pub fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    /// The lines of a random map over `u8` with a few entries that don't overlap, in input order.
+    pub fn random_map_lines(rng: &mut Rng) -> Vec<String> {
+        let mut lines = vec![];
+        let mut source_start = rng.below(40);
+
+        while lines.len() < 5 && source_start < 250 {
+            let len = 1 + rng.below((255 - source_start).min(60));
+            let dest_start = rng.below(256 - len);
+            lines.push(format!("{dest_start} {source_start} {len}"));
+
+            source_start += len + rng.below(30);
+        }
+
+        lines.reverse();
+        lines
+    }
+
+    fn random_map(rng: &mut Rng) -> RangeMap<u8> {
+        let section = format!("a-to-b map:\n{}", random_map_lines(rng).join("\n"));
+        section.as_str().try_into().unwrap()
+    }
+
+    #[test]
+    fn compose_matches_applying_both() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..300 {
+            let (first, second) = (random_map(&mut rng), random_map(&mut rng));
+            let composed = first.compose(&second);
+
+            for value in 0..=u8::MAX {
+                assert_eq!(composed.get(value), second.get(first.get(value)), "{value}");
+            }
+        }
+    }
+
+    #[test]
+    fn preimage_range_matches_brute_force() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..300 {
+            let map = &random_map(&mut rng);
+            let start = rng.below(255) as u8;
+            let end = start + 1 + rng.below((255 - start) as u64) as u8;
+
+            let pieces = map.preimage_range(start, end);
+            assert!(pieces
+                .windows(2)
+                .all(|pair| pair[0].dest_start <= pair[1].dest_start));
+
+            let mut from_pieces: Vec<u8> = pieces
+                .iter()
+                .flat_map(|piece| {
+                    (0..piece.len).map(move |offset| {
+                        let source = piece.source_start + offset;
+                        assert_eq!(map.get(source), piece.dest_start + offset);
+                        source
+                    })
+                })
+                .collect();
+            from_pieces.sort();
+
+            let expected: Vec<u8> = (0..u8::MAX)
+                .filter(|value| (start..end).contains(&map.get(*value)))
+                .collect();
+            assert_eq!(from_pieces, expected, "{start}..{end} under {map:?}");
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+/// The unsigned integer types the range machinery can work with.
+///
+/// Only `checked_add` is used on values that come from the input, plain `+` and `-` are kept for
+/// the spots where an earlier check already rules out overflow.
+pub trait Unsigned:
+    Copy + Ord + Hash + Default + Debug + Display + FromStr + Add<Output = Self> + Sub<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Unsigned for $ty {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$ty>::MAX;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, rhs)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
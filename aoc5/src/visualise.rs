@@ -0,0 +1,157 @@
+use std::fmt::Write;
+
+use crate::{almanac::Almanac, unsigned::Unsigned};
+
+const WIDTH: f64 = 1200.0;
+const MARGIN: f64 = 100.0;
+const STAGE_HEIGHT: f64 = 140.0;
+const LINE_THICKNESS: f64 = 10.0;
+
+struct Scale {
+    max: f64,
+}
+
+impl Scale {
+    fn x<T: Unsigned>(&self, value: T) -> f64 {
+        MARGIN + (value.as_f64() / self.max) * (WIDTH - 2.0 * MARGIN)
+    }
+}
+
+fn stage_y(stage: usize) -> f64 {
+    MARGIN / 2.0 + stage as f64 * STAGE_HEIGHT
+}
+
+/// Category names come from the map headers, so they can hold anything.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Draws a band from `source` on one stage's line to `dest` on the line below it.
+fn band<T: Unsigned>(
+    svg: &mut String,
+    scale: &Scale,
+    stage: usize,
+    source: (T, T),
+    dest: (T, T),
+    class: &str,
+) {
+    let top = stage_y(stage) + LINE_THICKNESS;
+    let bottom = stage_y(stage + 1);
+
+    writeln!(
+        svg,
+        r#"<polygon class="{class}" points="{:.2},{top} {:.2},{top} {:.2},{bottom} {:.2},{bottom}"/>"#,
+        scale.x(source.0),
+        scale.x(source.1),
+        scale.x(dest.1),
+        scale.x(dest.0),
+    )
+    .unwrap();
+}
+
+impl<T: Unsigned> Almanac<T> {
+    /// Renders every stage from seed to location as a number line, with the map entries drawn as
+    /// bands between the lines and the seed ranges overlaid as they split and move. `None` when
+    /// no chain of maps leads from seed to location.
+    pub fn to_svg(&self, seed_ranges: &[(T, T)]) -> Option<String> {
+        let path = self.path("seed", "location")?;
+
+        // Follow the seed ranges through every stage, splitting them whenever a map does
+        let mut stages: Vec<Vec<(T, T)>> = vec![seed_ranges.to_vec()];
+        for category_map in path.iter() {
+            let next = stages
+                .last()
+                .unwrap()
+                .iter()
+                .flat_map(|&(start, end)| category_map.map.split_range(start, end))
+                .map(|piece| (piece.dest_start, piece.dest_end()))
+                .collect();
+            stages.push(next);
+        }
+
+        let max = path
+            .iter()
+            .flat_map(|category_map| category_map.map.entries())
+            .flat_map(|entry| [entry.source_end(), entry.dest_end()])
+            .chain(stages.iter().flatten().map(|&(_, end)| end))
+            .max()
+            .map_or(1.0, |max| max.as_f64().max(1.0));
+        let scale = Scale { max };
+
+        let height = stage_y(path.len()) + MARGIN / 2.0 + LINE_THICKNESS;
+        let mut svg = String::new();
+
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" font-family="monospace" font-size="12">"#
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<style>.entry {{ fill: #8888ff; fill-opacity: 0.25; stroke: #4444aa; stroke-width: 0.5 }} \
+             .seed {{ fill: #ff8800; fill-opacity: 0.6 }} .line {{ fill: #cccccc }}</style>"
+        )
+        .unwrap();
+
+        let categories = std::iter::once("seed").chain(path.iter().map(|map| map.target.as_str()));
+        for (stage, category) in categories.enumerate() {
+            let y = stage_y(stage);
+            writeln!(
+                svg,
+                r#"<text x="4" y="{:.2}">{}</text>"#,
+                y + LINE_THICKNESS,
+                escape(category)
+            )
+            .unwrap();
+            writeln!(
+                svg,
+                r#"<rect class="line" x="{MARGIN}" y="{y}" width="{}" height="{LINE_THICKNESS}"/>"#,
+                WIDTH - 2.0 * MARGIN
+            )
+            .unwrap();
+
+            for &(start, end) in stages[stage].iter() {
+                writeln!(
+                    svg,
+                    r#"<rect class="seed" x="{:.2}" y="{y}" width="{:.2}" height="{LINE_THICKNESS}"><title>{start}..{}</title></rect>"#,
+                    scale.x(start),
+                    (scale.x(end) - scale.x(start)).max(1.0),
+                    end
+                )
+                .unwrap();
+            }
+        }
+
+        for (stage, category_map) in path.iter().enumerate() {
+            for entry in category_map.map.entries() {
+                band(
+                    &mut svg,
+                    &scale,
+                    stage,
+                    (entry.source_start, entry.source_end()),
+                    (entry.dest_start, entry.dest_end()),
+                    "entry",
+                );
+            }
+
+            for &(start, end) in stages[stage].iter() {
+                for piece in category_map.map.split_range(start, end) {
+                    band(
+                        &mut svg,
+                        &scale,
+                        stage,
+                        (piece.source_start, piece.source_end()),
+                        (piece.dest_start, piece.dest_end()),
+                        "seed",
+                    );
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        Some(svg)
+    }
+}
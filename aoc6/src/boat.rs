@@ -0,0 +1,241 @@
+use std::ops::RangeInclusive;
+
+use crate::Race;
+
+/// How far a boat gets in a race, depending on how long the button was held.
+///
+/// The numeric solver assumes the distance only rises and then falls as the press time grows,
+/// which holds for every model below.
+pub trait BoatModel {
+    fn distance_travelled(&self, press_time: u64, race_time: u64) -> u128;
+
+    /// All the press times that beat the record.
+    fn winning_window(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        numeric_window(self, race)
+    }
+}
+
+/// Speed goes up by one for every millisecond the button is held, like in the puzzle.
+pub struct LinearCharge;
+
+impl BoatModel for LinearCharge {
+    fn distance_travelled(&self, press_time: u64, race_time: u64) -> u128 {
+        // At most (race_time / 2)^2, which always fits
+        press_time as u128 * (race_time - press_time) as u128
+    }
+
+    /// Found from the roots of `t * (time - t) = distance` instead of trying every press time.
+    fn winning_window(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        let time = race.time as u128;
+        let discriminant = (time * time).checked_sub(4 * race.distance as u128)?;
+        let wins =
+            |press_time| self.distance_travelled(press_time, race.time) > race.distance as u128;
+
+        // The lower root is (time - sqrt(discriminant)) / 2. Integer square roots can land one
+        // step on either side of the real boundary, so nudge it until it is exact.
+        let mut lower = ((time - discriminant.isqrt()) / 2) as u64;
+        let midpoint = race.time / 2;
+
+        while lower <= midpoint && !wins(lower) {
+            lower += 1;
+        }
+        while lower > 0 && wins(lower - 1) {
+            lower -= 1;
+        }
+
+        if lower > midpoint {
+            return None;
+        }
+
+        // The parabola is symmetric around time / 2
+        Some(lower..=race.time - lower)
+    }
+}
+
+/// Speed is the square of the press time.
+pub struct QuadraticCharge;
+
+impl BoatModel for QuadraticCharge {
+    fn distance_travelled(&self, press_time: u64, race_time: u64) -> u128 {
+        (press_time as u128)
+            .saturating_mul(press_time as u128)
+            .saturating_mul((race_time - press_time) as u128)
+    }
+}
+
+/// Charges like [`LinearCharge`] but can't go faster than `max_speed`.
+pub struct CappedSpeed {
+    pub max_speed: u64,
+}
+
+impl BoatModel for CappedSpeed {
+    fn distance_travelled(&self, press_time: u64, race_time: u64) -> u128 {
+        press_time.min(self.max_speed) as u128 * (race_time - press_time) as u128
+    }
+}
+
+/// Charges like [`LinearCharge`], then loses `deceleration` speed every millisecond once
+/// released until it stops.
+pub struct ConstantDrag {
+    pub deceleration: u64,
+}
+
+impl BoatModel for ConstantDrag {
+    fn distance_travelled(&self, press_time: u64, race_time: u64) -> u128 {
+        let speed = press_time as u128;
+        let deceleration = self.deceleration as u128;
+        let travel_time = (race_time - press_time) as u128;
+
+        // Number of milliseconds the boat is still moving, then an arithmetic series over them
+        let moving_time = match deceleration {
+            0 => travel_time,
+            _ => travel_time.min(speed.div_ceil(deceleration)),
+        };
+
+        moving_time * speed - deceleration * (moving_time * moving_time.saturating_sub(1) / 2)
+    }
+}
+
+/// Finds the fastest press time with a binary search on the slope, then binary searches for the
+/// record on both sides of it.
+fn numeric_window<M: BoatModel + ?Sized>(model: &M, race: &Race) -> Option<RangeInclusive<u64>> {
+    let distance = |press_time| model.distance_travelled(press_time, race.time);
+    let record = race.distance as u128;
+
+    // First press time after which the distance stops going up
+    let (mut low, mut high) = (0, race.time);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if distance(mid) < distance(mid + 1) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    let peak = low;
+
+    if distance(peak) <= record {
+        return None;
+    }
+
+    // Smallest winning press time on the rising side
+    let (mut low, mut high) = (0, peak);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if distance(mid) > record {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    let lower = low;
+
+    // Largest winning press time on the falling side
+    let (mut low, mut high) = (peak, race.time);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if distance(mid) > record {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Some(lower..=low)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn race(time: u64, distance: u64) -> Race {
+        Race {
+            time,
+            distance,
+            parameters: HashMap::new(),
+        }
+    }
+
+    /// The winning press times found by trying every one of them.
+    fn brute_force<M: BoatModel>(model: &M, race: &Race) -> Option<RangeInclusive<u64>> {
+        let wins: Vec<u64> = (0..=race.time)
+            .filter(|&press_time| {
+                model.distance_travelled(press_time, race.time) > race.distance as u128
+            })
+            .collect();
+
+        // Every model rises and then falls, so the winners have to be one unbroken window
+        if let (Some(first), Some(last)) = (wins.first(), wins.last()) {
+            assert_eq!(wins.len() as u64, last - first + 1, "{race:?}");
+        }
+        Some(*wins.first()?..=*wins.last()?)
+    }
+
+    /// Every short race against every record that matters: each distance the boat can reach and
+    /// the ones right next to it.
+    fn check_model<M: BoatModel>(model: &M) {
+        for time in 0..60 {
+            let mut records: Vec<u64> = (0..=time)
+                .map(|press_time| model.distance_travelled(press_time, time) as u64)
+                .flat_map(|distance| [distance.saturating_sub(1), distance, distance + 1])
+                .collect();
+            records.sort();
+            records.dedup();
+
+            for distance in records {
+                let race = race(time, distance);
+                let expected = brute_force(model, &race);
+
+                assert_eq!(model.winning_window(&race), expected, "{race:?}");
+                assert_eq!(numeric_window(model, &race), expected, "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn windows_match_brute_force() {
+        check_model(&LinearCharge);
+        check_model(&QuadraticCharge);
+        for max_speed in 0..8 {
+            check_model(&CappedSpeed { max_speed });
+        }
+        for deceleration in 0..8 {
+            check_model(&ConstantDrag { deceleration });
+        }
+    }
+
+    #[test]
+    fn longest_race() {
+        let time = u64::MAX;
+
+        for window in [
+            LinearCharge.winning_window(&race(time, 0)),
+            numeric_window(&LinearCharge, &race(time, 0)),
+        ] {
+            assert_eq!(window, Some(1..=time - 1));
+        }
+
+        // 1 * (time - 1) falls one short of the record, 2 * (time - 2) beats it
+        for window in [
+            LinearCharge.winning_window(&race(time, u64::MAX)),
+            numeric_window(&LinearCharge, &race(time, u64::MAX)),
+        ] {
+            assert_eq!(window, Some(2..=time - 2));
+        }
+
+        let capped = CappedSpeed { max_speed: 1 };
+        assert_eq!(capped.winning_window(&race(time, u64::MAX)), None);
+        assert_eq!(
+            capped.winning_window(&race(time, u64::MAX - 2)),
+            Some(1..=1)
+        );
+
+        let drag = ConstantDrag { deceleration: 0 };
+        assert_eq!(
+            drag.winning_window(&race(time, u64::MAX)),
+            Some(2..=time - 2)
+        );
+    }
+}
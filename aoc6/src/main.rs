@@ -1,47 +1,39 @@
+mod boat;
+mod sheet;
+
+use std::collections::HashMap;
+
+use boat::{BoatModel, CappedSpeed, ConstantDrag, LinearCharge, QuadraticCharge};
+use sheet::Sheet;
+
 #[derive(Debug)]
 struct Race {
-    time: usize,
-    distance: usize,
+    time: u64,
+    distance: u64,
+    /// Values from any extra rows on the sheet, keyed by row label
+    parameters: HashMap<String, u64>,
 }
 
-fn parse_input() -> Vec<Race> {
-    let input = std::fs::read_to_string("input").unwrap();
-
-    let mut numbers = input.lines().map(|line| {
-        line.split(": ")
-            .nth(1)
-            .unwrap()
-            .split_ascii_whitespace()
-            .map(|num_str| num_str.parse::<usize>().unwrap())
-    });
-
-    let times = numbers.next().unwrap();
-    let distances = numbers.next().unwrap();
+impl Race {
+    pub fn ways_to_win<M: BoatModel>(&self, model: &M) -> u64 {
+        model
+            .winning_window(self)
+            .map_or(0, |window| window.end() - window.start() + 1)
+    }
+}
 
-    times
-        .zip(distances)
-        .map(|(time, distance)| Race { time, distance })
-        .collect()
+fn parse_input() -> Sheet {
+    std::fs::read_to_string("input").unwrap().as_str().into()
 }
 
 mod part1 {
     use crate::*;
 
     pub fn solution() {
-        let product_of_ways: usize = parse_input()
-            .into_iter()
-            .map(|race| {
-                // All the possible lengths of time you can press the button for
-                let button_press_times = 0..=race.time;
-                button_press_times
-                    .filter(|press_time| {
-                        let speed = press_time;
-                        let travel_time = race.time - press_time;
-                        let distance_travelled = speed * travel_time;
-                        distance_travelled > race.distance
-                    })
-                    .count()
-            })
+        let product_of_ways: u64 = parse_input()
+            .races()
+            .iter()
+            .map(|race| race.ways_to_win(&LinearCharge))
             .product();
 
         println!("(Part 1) Product of ways: {product_of_ways}");
@@ -51,31 +43,62 @@ mod part1 {
 mod part2 {
     use crate::*;
     pub fn solution() {
-        let original_input = parse_input();
-
-        let time = original_input.iter().fold(String::from(""), |acc, race| {
-            acc + race.time.to_string().as_str()
-        }).parse::<usize>().unwrap();
-        let distance = original_input.iter().fold(String::from(""), |acc, race| {
-            acc + race.distance.to_string().as_str()
-        }).parse::<usize>().unwrap();
-
-        let button_press_times = 0..=time;
-        let number_of_ways = button_press_times
-                .filter(|press_time| {
-                    let speed = press_time;
-                    let travel_time = time - press_time;
-                    let distance_travelled = speed * travel_time;
-                    distance_travelled > distance
-                })
-                .count();
-
-        println!("(Part 2) Number of ways: {number_of_ways}"); 
+        let race = parse_input().kerned_race();
 
+        match LinearCharge.winning_window(&race) {
+            Some(window) => println!(
+                "(Part 2) Number of ways: {} (hold for {} to {} ms)",
+                race.ways_to_win(&LinearCharge),
+                window.start(),
+                window.end()
+            ),
+            None => println!("(Part 2) Number of ways: 0"),
+        }
     }
 }
 
+/// Runs the part 1 races under a different boat model, built separately for every race so it
+/// can use that race's parameters.
+fn variant<M: BoatModel>(model: impl Fn(&Race) -> M) {
+    let product_of_ways: u64 = parse_input()
+        .races()
+        .iter()
+        .map(|race| race.ways_to_win(&model(race)))
+        .product();
+
+    println!("Product of ways: {product_of_ways}");
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: aoc6 [linear | quadratic | capped <max speed> | drag <deceleration>]");
+    std::process::exit(1)
+}
+
 fn main() {
-    part1::solution();
-    part2::solution();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Either a number, or the label of a sheet row to take the number from
+    let parameter = |race: &Race| match args[1].parse::<u64>() {
+        Ok(value) => value,
+        Err(_) => *race
+            .parameters
+            .get(&args[1])
+            .unwrap_or_else(|| panic!("Race sheet has no {} row", args[1])),
+    };
+
+    match args.first().map(|arg| arg.as_str()) {
+        Some("capped" | "drag") if args.len() < 2 => usage(),
+        Some("linear") => variant(|_| LinearCharge),
+        Some("quadratic") => variant(|_| QuadraticCharge),
+        Some("capped") => variant(|race| CappedSpeed {
+            max_speed: parameter(race),
+        }),
+        Some("drag") => variant(|race| ConstantDrag {
+            deceleration: parameter(race),
+        }),
+        _ => {
+            part1::solution();
+            part2::solution();
+        }
+    }
 }
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use crate::Race;
+
+/// One `Label: 1 2 3` row, with the numbers kept exactly as written.
+#[derive(Debug)]
+pub struct SheetRow {
+    pub label: String,
+    pub cells: Vec<String>,
+}
+
+/// The race sheet, which can be read as separate races or as one race with the spaces removed.
+#[derive(Debug)]
+pub struct Sheet(Vec<SheetRow>);
+
+impl Sheet {
+    pub fn row(&self, label: &str) -> Option<&SheetRow> {
+        self.0.iter().find(|row| row.label == label)
+    }
+
+    /// Every row besides Time and Distance, which hold per race parameters like "Charge".
+    fn parameter_rows(&self) -> impl Iterator<Item = &SheetRow> {
+        self.0
+            .iter()
+            .filter(|row| row.label != "Time" && row.label != "Distance")
+    }
+
+    fn required_row(&self, label: &str) -> &SheetRow {
+        self.row(label)
+            .unwrap_or_else(|| panic!("Sheet is missing a {label} row"))
+    }
+
+    /// Reads every column as its own race.
+    pub fn races(&self) -> Vec<Race> {
+        let times = &self.required_row("Time").cells;
+        let distances = &self.required_row("Distance").cells;
+
+        if times.len() != distances.len() {
+            panic!(
+                "Sheet has {} times but {} distances",
+                times.len(),
+                distances.len()
+            );
+        }
+
+        (0..times.len())
+            .map(|column| Race {
+                time: parse_cell(&times[column]),
+                distance: parse_cell(&distances[column]),
+                parameters: self
+                    .parameter_rows()
+                    .filter_map(|row| {
+                        let cell = row.cells.get(column)?;
+                        Some((row.label.clone(), parse_cell(cell)))
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Reads the whole sheet as one race by joining the digits of every row.
+    pub fn kerned_race(&self) -> Race {
+        let kerned = |row: &SheetRow| parse_cell(&row.cells.concat());
+
+        Race {
+            time: kerned(self.required_row("Time")),
+            distance: kerned(self.required_row("Distance")),
+            parameters: self
+                .parameter_rows()
+                .map(|row| (row.label.clone(), kerned(row)))
+                .collect::<HashMap<String, u64>>(),
+        }
+    }
+}
+
+fn parse_cell(cell: &str) -> u64 {
+    cell.parse::<u64>()
+        .unwrap_or_else(|_| panic!("Invalid number on race sheet: {cell}"))
+}
+
+impl From<&str> for Sheet {
+    fn from(input: &str) -> Self {
+        Self(
+            input
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    let (label, cells) = line
+                        .split_once(':')
+                        .unwrap_or_else(|| panic!("Invalid race sheet row: {line}"));
+
+                    SheetRow {
+                        label: label.trim().to_owned(),
+                        cells: cells
+                            .split_ascii_whitespace()
+                            .map(|cell| cell.to_owned())
+                            .collect(),
+                    }
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,569 @@
+use std::{cmp::Ordering, collections::HashSet, fmt};
+
+pub const MIN_HAND_SIZE: usize = 3;
+pub const MAX_HAND_SIZE: usize = 7;
+/// Ranks start at 1 and have to fit in a nibble
+pub const MAX_LABELS: usize = 15;
+
+#[derive(Eq, PartialEq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub struct Card {
+    pub label: char,
+    pub suit: Option<char>,
+}
+
+impl Card {
+    pub fn unsuited(label: char) -> Card {
+        Card { label, suit: None }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.suit {
+            Some(suit) => write!(f, "{}{}", self.label, suit),
+            None => write!(f, "{}", self.label),
+        }
+    }
+}
+
+/// The sizes of the groups of equal labels in a hand, largest first and padded with zeroes.
+///
+/// Comparing these from left to right gives the puzzle's order for five cards (five of a kind
+/// `[5]` beats four of a kind `[4, 1]` beats a full house `[3, 2]` and so on) and carries over to
+/// any other hand size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandKind {
+    pub groups: [u8; MAX_HAND_SIZE],
+}
+
+impl HandKind {
+    /// Sorts the counts in place, after which the groups are at the front. A hand has at most
+    /// `MAX_HAND_SIZE` cards, so there can't be more groups than that.
+    fn from_counts(mut counts: [u8; MAX_LABELS + 1]) -> HandKind {
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut groups = [0; MAX_HAND_SIZE];
+        groups.copy_from_slice(&counts[..MAX_HAND_SIZE]);
+
+        HandKind { groups }
+    }
+
+    fn sizes(&self) -> &[u8] {
+        let len = self.groups.iter().take_while(|size| **size > 0).count();
+        &self.groups[..len]
+    }
+
+    /// One nibble per group, for use in sort keys.
+    fn packed(&self) -> u128 {
+        self.groups
+            .iter()
+            .fold(0, |packed, size| (packed << 4) | *size as u128)
+    }
+}
+
+impl fmt::Display for HandKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.sizes() {
+            [5] => "Five of a kind".to_owned(),
+            [4, 1] => "Four of a kind".to_owned(),
+            [3, 2] => "Full house".to_owned(),
+            [3, 1, 1] => "Three of a kind".to_owned(),
+            [2, 2, 1] => "Two pair".to_owned(),
+            [2, 1, 1, 1] => "One pair".to_owned(),
+            [1, 1, 1, 1, 1] => "High card".to_owned(),
+            // Other hand sizes don't have names, so just list the groups: 3+2+2
+            sizes => {
+                let sizes: Vec<String> = sizes.iter().map(|size| size.to_string()).collect();
+                sizes.join("+")
+            }
+        };
+
+        f.pad(&name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidCard(String),
+    WrongHandSize { hand: String, expected: usize },
+    TooManyCopies { hand: String, card: String },
+    InvalidLine(String),
+    TooManyLabels(usize),
+    DuplicateLabel(char),
+    UnknownWildcard(char),
+    InvalidHandSize(usize),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidCard(card) => write!(f, "Invalid card: {card}"),
+            ParseError::WrongHandSize { hand, expected } => {
+                write!(f, "Hand {hand} does not have {expected} cards")
+            }
+            ParseError::TooManyCopies { hand, card } => {
+                write!(
+                    f,
+                    "Hand {hand} has more copies of {card} than the decks hold"
+                )
+            }
+            ParseError::InvalidLine(line) => write!(f, "Invalid line: {line}"),
+            ParseError::TooManyLabels(labels) => write!(
+                f,
+                "Card order has {labels} labels, at most {MAX_LABELS} are supported"
+            ),
+            ParseError::DuplicateLabel(label) => {
+                write!(f, "Label {label} is in the card order more than once")
+            }
+            ParseError::UnknownWildcard(label) => {
+                write!(f, "Wildcard {label} is not in the card order")
+            }
+            ParseError::InvalidHandSize(size) => write!(
+                f,
+                "Hands of {size} cards are not supported, use {MIN_HAND_SIZE} to {MAX_HAND_SIZE}"
+            ),
+        }
+    }
+}
+
+/// How two hands of the same kind are told apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TieBreak {
+    /// Compare the cards one by one in the order they were dealt, like the puzzle does
+    DealtOrder,
+    /// Compare the highest cards first like in poker, falling back to the dealt order
+    HighestFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ruleset {
+    /// Every label from weakest to strongest, at most `MAX_LABELS` of them
+    pub card_order: String,
+    /// Labels that count as whichever card makes the best hand
+    pub wildcards: String,
+    pub tie_break: TieBreak,
+    /// Between `MIN_HAND_SIZE` and `MAX_HAND_SIZE`
+    pub hand_size: usize,
+    /// When not empty every card is written as a label followed by one of these suits
+    pub suits: String,
+    /// Every distinct card is in each deck once, so this caps how often it can show up in a
+    /// hand. The puzzle has no such limit.
+    pub decks: Option<usize>,
+}
+
+impl Ruleset {
+    pub fn part1() -> Ruleset {
+        Ruleset {
+            card_order: "23456789TJQKA".to_owned(),
+            wildcards: "".to_owned(),
+            tie_break: TieBreak::DealtOrder,
+            hand_size: 5,
+            suits: "".to_owned(),
+            decks: None,
+        }
+    }
+
+    /// Jacks become jokers, which are wild but the weakest card on their own.
+    pub fn part2() -> Ruleset {
+        Ruleset {
+            card_order: "J23456789TQKA".to_owned(),
+            wildcards: "J".to_owned(),
+            ..Ruleset::part1()
+        }
+    }
+
+    /// Checks the parts of a ruleset that don't come from the puzzle, so hands can rely on them.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if !(MIN_HAND_SIZE..=MAX_HAND_SIZE).contains(&self.hand_size) {
+            return Err(ParseError::InvalidHandSize(self.hand_size));
+        }
+
+        let labels = self.card_order.chars().count();
+        if labels > MAX_LABELS {
+            return Err(ParseError::TooManyLabels(labels));
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(label) = self.card_order.chars().find(|label| !seen.insert(*label)) {
+            return Err(ParseError::DuplicateLabel(label));
+        }
+
+        if let Some(label) = self.wildcards.chars().find(|label| !seen.contains(label)) {
+            return Err(ParseError::UnknownWildcard(label));
+        }
+
+        Ok(())
+    }
+
+    /// Strength of a card starting at 1, so every card fits in a nibble.
+    pub fn rank(&self, card: Card) -> usize {
+        match self
+            .card_order
+            .chars()
+            .position(|label| label == card.label)
+        {
+            Some(index) => index + 1,
+            None => panic!("Invalid card: {card}"),
+        }
+    }
+
+    /// One unsuited card for every label.
+    pub fn cards(&self) -> Vec<Card> {
+        self.card_order.chars().map(Card::unsuited).collect()
+    }
+
+    /// Every distinct card, one per label and suit when suits are used.
+    pub fn deck(&self) -> Vec<Card> {
+        if self.suits.is_empty() {
+            return self.cards();
+        }
+
+        self.card_order
+            .chars()
+            .flat_map(|label| {
+                self.suits.chars().map(move |suit| Card {
+                    label,
+                    suit: Some(suit),
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Hand<'r> {
+    pub cards: Vec<Card>,
+    pub rules: &'r Ruleset,
+}
+
+impl<'r> Hand<'r> {
+    pub fn parse(hand: &str, rules: &'r Ruleset) -> Result<Hand<'r>, ParseError> {
+        let mut chars = hand.chars();
+        let mut cards = vec![];
+
+        while let Some(label) = chars.next() {
+            if !rules.card_order.contains(label) {
+                return Err(ParseError::InvalidCard(label.to_string()));
+            }
+
+            let suit = match rules.suits.is_empty() {
+                true => None,
+                false => match chars.next() {
+                    Some(suit) if rules.suits.contains(suit) => Some(suit),
+                    Some(suit) => return Err(ParseError::InvalidCard(format!("{label}{suit}"))),
+                    None => return Err(ParseError::InvalidCard(label.to_string())),
+                },
+            };
+
+            cards.push(Card { label, suit });
+        }
+
+        if cards.len() != rules.hand_size {
+            return Err(ParseError::WrongHandSize {
+                hand: hand.to_owned(),
+                expected: rules.hand_size,
+            });
+        }
+
+        if let Some(decks) = rules.decks {
+            for card in cards.iter() {
+                if cards.iter().filter(|other| *other == card).count() > decks {
+                    return Err(ParseError::TooManyCopies {
+                        hand: hand.to_owned(),
+                        card: card.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(Hand { cards, rules })
+    }
+
+    /// How many times each label appears, indexed by rank. Suits don't matter here.
+    fn counts(&self) -> [u8; MAX_LABELS + 1] {
+        let mut counts = [0; MAX_LABELS + 1];
+        for card in self.cards.iter() {
+            counts[self.rules.rank(*card)] += 1;
+        }
+        counts
+    }
+
+    pub fn kind(&self) -> HandKind {
+        let mut counts = self.counts();
+        let mut wildcards = 0;
+        for card in self.rules.wildcards.chars().map(Card::unsuited) {
+            wildcards += std::mem::take(&mut counts[self.rules.rank(card)]);
+        }
+
+        // Wildcards are always worth the most when they all join the largest group. With only
+        // wildcards in the hand every count is zero and they simply become one big group.
+        let largest = (0..counts.len()).max_by_key(|&i| counts[i]).unwrap();
+        counts[largest] += wildcards;
+
+        HandKind::from_counts(counts)
+    }
+
+    /// The kind in the top bits followed by the ranks of the cards in tie break order, so
+    /// comparing keys is the same as comparing hands.
+    pub fn sort_key(&self) -> u128 {
+        let mut ranks = [0; MAX_HAND_SIZE];
+        for (rank, card) in ranks.iter_mut().zip(self.cards.iter()) {
+            *rank = self.rules.rank(*card);
+        }
+        let len = self.cards.len();
+        let push = |key: u128, rank: &usize| (key << 4) | *rank as u128;
+
+        let key = self.kind().packed();
+        match self.rules.tie_break {
+            TieBreak::DealtOrder => ranks[..len].iter().fold(key, push),
+            TieBreak::HighestFirst => {
+                let mut highest_first = ranks;
+                highest_first[..len].sort_unstable_by(|a, b| b.cmp(a));
+
+                let key = highest_first[..len].iter().fold(key, push);
+                ranks[..len].iter().fold(key, push)
+            }
+        }
+    }
+}
+
+/// Hands are ordered by the puzzle rules first. Within one ruleset the sort key already tells
+/// every pair of different hands apart, the cards and rules only come into play to keep the
+/// order total (and in line with `Eq`) for hands from different rulesets, and for hands that
+/// differ only in their suits.
+impl Ord for Hand<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key()
+            .cmp(&other.sort_key())
+            .then_with(|| self.cards.cmp(&other.cards))
+            .then_with(|| self.rules.cmp(other.rules))
+    }
+}
+
+impl PartialOrd for Hand<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bid<'r> {
+    pub hand: Hand<'r>,
+    pub bid: usize,
+}
+
+impl Bid<'_> {
+    pub fn calculate_winnings(&self, rank: usize) -> usize {
+        self.bid * rank
+    }
+}
+
+pub fn parse_input(rules: &Ruleset) -> Result<Vec<Bid<'_>>, ParseError> {
+    std::fs::read_to_string("input")
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let (hand, bid) = line
+                .split_once(' ')
+                .ok_or_else(|| ParseError::InvalidLine(line.to_owned()))?;
+
+            Ok(Bid {
+                hand: Hand::parse(hand, rules)?,
+                bid: bid
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::InvalidLine(line.to_owned()))?,
+            })
+        })
+        .collect()
+}
+
+pub fn total_winnings(rules: &Ruleset) -> usize {
+    let mut bids = parse_input(rules).unwrap_or_else(|err| panic!("{err}"));
+    // Encode every hand once, the sort itself only compares integers
+    bids.sort_by_cached_key(|bid| bid.hand.sort_key());
+
+    bids.iter()
+        .enumerate()
+        .map(|(index, bid)| bid.calculate_winnings(index + 1))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    impl Hand<'_> {
+        /// Reference implementation for `kind` with wildcards that tries every substitution.
+        /// Where each wildcard goes doesn't change the kind, so only the multisets of labels they
+        /// could turn into are tried.
+        fn brute_force_kind(&self) -> HandKind {
+            let mut counts = self.counts();
+            let wild_ranks: Vec<usize> = self
+                .rules
+                .wildcards
+                .chars()
+                .map(|label| self.rules.rank(Card::unsuited(label)))
+                .collect();
+            let wildcards = wild_ranks
+                .iter()
+                .map(|&rank| std::mem::take(&mut counts[rank]))
+                .sum();
+
+            let labels: Vec<usize> = (1..=self.rules.card_order.chars().count())
+                .filter(|rank| !wild_ranks.contains(rank))
+                .collect();
+            best_substitution(counts, wildcards, &labels)
+        }
+    }
+
+    /// Hands out `wildcards` extra cards over `labels`, never to a label before the one the
+    /// previous card went to, so every multiset comes up exactly once.
+    fn best_substitution(
+        counts: [u8; MAX_LABELS + 1],
+        wildcards: u8,
+        labels: &[usize],
+    ) -> HandKind {
+        if wildcards == 0 {
+            return HandKind::from_counts(counts);
+        }
+
+        (0..labels.len())
+            .map(|i| {
+                let mut counts = counts;
+                counts[labels[i]] += 1;
+                best_substitution(counts, wildcards - 1, &labels[i..])
+            })
+            .max()
+            .unwrap()
+    }
+
+    /// Compares the wildcard rule against the brute force substitution for every possible hand.
+    fn check_wildcard_rule(rules: &Ruleset) {
+        let cards = rules.cards();
+
+        for index in 0..cards.len().pow(rules.hand_size as u32) {
+            let hand = Hand {
+                cards: (0..rules.hand_size as u32)
+                    .map(|position| cards[index / cards.len().pow(position) % cards.len()])
+                    .collect(),
+                rules,
+            };
+
+            assert_eq!(
+                hand.kind(),
+                hand.brute_force_kind(),
+                "Wildcard rule disagrees with brute force for {:?}",
+                hand.cards
+            );
+        }
+    }
+
+    #[test]
+    fn wildcard_rule_small_hands() {
+        check_wildcard_rule(&Ruleset {
+            hand_size: 3,
+            ..Ruleset::part2()
+        });
+    }
+
+    /// All 13^5 hands
+    #[test]
+    fn wildcard_rule_all_hands() {
+        check_wildcard_rule(&Ruleset::part2());
+    }
+
+    /// Random bids from a few rulesets, from a handful of labels so that equal hands actually come
+    /// up.
+    fn random_triples(seed: u64, rounds: usize, check: impl Fn([&Bid; 3])) {
+        let rulesets = [
+            Ruleset::part1(),
+            Ruleset::part2(),
+            Ruleset {
+                card_order: "A23456789TJQK".to_owned(),
+                wildcards: "Q".to_owned(),
+                tie_break: TieBreak::HighestFirst,
+                hand_size: 7,
+                suits: "cdhs".to_owned(),
+                decks: Some(2),
+            },
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let random_bid = |rng: &mut ChaCha8Rng| {
+            let rules = rulesets.choose(rng).unwrap();
+            let deck = rules.deck();
+            let cards = &deck[..rng.gen_range(2..=deck.len())];
+
+            Bid {
+                hand: Hand {
+                    cards: (0..rules.hand_size)
+                        .map(|_| *cards.choose(rng).unwrap())
+                        .collect(),
+                    rules,
+                },
+                bid: rng.gen_range(1..=3),
+            }
+        };
+
+        for _ in 0..rounds {
+            let [a, b, c] = [0; 3].map(|_| random_bid(&mut rng));
+            check([&a, &b, &c]);
+        }
+    }
+
+    /// `PartialOrd` agrees with `Ord`, `Ord` with `Eq`, and swapping the sides reverses the order.
+    fn check_consistent<T: Ord + std::fmt::Debug>(x: &T, y: &T) {
+        assert_eq!(x.partial_cmp(y), Some(x.cmp(y)), "{x:?} {y:?}");
+        assert_eq!(x == y, x.cmp(y) == Ordering::Equal, "{x:?} {y:?}");
+        assert_eq!(x.cmp(y), y.cmp(x).reverse(), "{x:?} {y:?}");
+    }
+
+    /// Tries the triple in every order, so no comparison is left out.
+    fn check_transitive<T: Ord + std::fmt::Debug>(triple: [&T; 3]) {
+        for [x, y, z] in [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ] {
+            let [x, y, z] = [triple[x], triple[y], triple[z]];
+            if x <= y && y <= z {
+                assert!(x <= z, "{x:?} {y:?} {z:?}");
+            }
+            if x == y && y == z {
+                assert!(x == z, "{x:?} {y:?} {z:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn ordering_is_consistent() {
+        random_triples(0, 5_000, |[a, b, c]| {
+            for (x, y) in [(a, b), (b, c), (a, c), (a, a)] {
+                check_consistent(x, y);
+                check_consistent(&x.hand, &y.hand);
+                check_consistent(&y.hand, &x.hand);
+
+                // Within a ruleset a better kind always wins
+                for (x, y) in [(&x.hand, &y.hand), (&y.hand, &x.hand)] {
+                    if x.rules == y.rules && x.kind() > y.kind() {
+                        assert_eq!(x.cmp(y), Ordering::Greater, "{x:?} {y:?}");
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn ordering_is_transitive() {
+        random_triples(1, 5_000, |[a, b, c]| {
+            check_transitive([a, b, c]);
+            check_transitive([&a.hand, &b.hand, &c.hand]);
+        });
+    }
+}
@@ -1,7 +1,98 @@
+mod camel_cards;
 mod part1;
 mod part2;
+mod simulator;
+
+use std::fmt::Display;
+
+use camel_cards::{Hand, Ruleset, TieBreak};
+use simulator::Simulator;
+
+fn fail(err: impl Display) -> ! {
+    eprintln!("{err}");
+    std::process::exit(1)
+}
+
+/// A ruleset is either "part1", "part2" or
+/// `<card order> <wildcards> <dealt | highest> [hand size] [suits] [decks]`.
+fn parse_ruleset(args: &[String]) -> Result<Ruleset, String> {
+    let rules = match args {
+        [preset] if preset == "part1" => Ruleset::part1(),
+        [preset] if preset == "part2" => Ruleset::part2(),
+        [card_order, wildcards, tie_break, rest @ ..] if rest.len() <= 3 => {
+            let number = |arg: &String| {
+                arg.parse::<usize>()
+                    .map_err(|_| format!("Invalid number: {arg}"))
+            };
+
+            Ruleset {
+                card_order: card_order.clone(),
+                wildcards: wildcards.clone(),
+                tie_break: match tie_break.as_str() {
+                    "dealt" => TieBreak::DealtOrder,
+                    "highest" => TieBreak::HighestFirst,
+                    tie_break => return Err(format!("Invalid tie break: {tie_break}")),
+                },
+                hand_size: rest.first().map_or(Ok(5), number)?,
+                suits: rest.get(1).cloned().unwrap_or_default(),
+                decks: rest.get(2).map(number).transpose()?,
+            }
+        }
+        _ => return Err(format!("Invalid ruleset: {}", args.join(" "))),
+    };
+
+    rules.validate().map_err(|err| err.to_string())?;
+    Ok(rules)
+}
 
 fn main() {
-    part1::solution();
-    part2::solution();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(|arg| arg.as_str()) {
+        // Usage: aoc7 simulate <hand> <bid> <opponents> <seed> <ruleset>
+        // e.g. "aoc7 simulate 2S2H7C7DQS 10 4 0 23456789TJQKA Q dealt 5 SHCD 1"
+        Some("simulate") if args.len() > 5 => {
+            let rules = parse_ruleset(&args[5..]).unwrap_or_else(|err| fail(err));
+            let hand = Hand::parse(&args[1], &rules).unwrap_or_else(|err| fail(err));
+            let bid = args[2].parse::<usize>().unwrap_or_else(|err| fail(err));
+            let opponents = args[3].parse::<usize>().unwrap_or_else(|err| fail(err));
+            let seed = args[4].parse::<u64>().unwrap_or_else(|err| fail(err));
+
+            let mut simulator = Simulator::new(&rules, seed);
+            let samples = 100_000;
+
+            println!("Hand kinds in {samples} random hands:");
+            let frequencies = simulator
+                .kind_frequencies(samples)
+                .unwrap_or_else(|err| fail(err));
+            for (kind, count) in frequencies {
+                let percentage = 100.0 * count as f64 / samples as f64;
+                println!("  {kind:<16} {count:>7} ({percentage:.3}%)");
+            }
+
+            let result = simulator
+                .tournament(&hand, opponents, samples)
+                .unwrap_or_else(|err| fail(err));
+            println!(
+                "{} against {opponents} opponents: wins {:.3}% of rounds, expected rank {:.3}, expected winnings for a bid of {bid}: {:.3}",
+                args[1],
+                100.0 * result.win_probability(),
+                result.expected_rank(),
+                result.expected_winnings(bid)
+            );
+        }
+        // Usage: aoc7 play <ruleset>
+        // e.g. "aoc7 play A23456789TJQK Q dealt" for aces low with queens wild
+        Some("play") if args.len() > 1 => {
+            let rules = parse_ruleset(&args[1..]).unwrap_or_else(|err| fail(err));
+            let total_winnings = camel_cards::total_winnings(&rules);
+            println!("Total winnings: {total_winnings}");
+        }
+        Some("simulate") => fail("Usage: aoc7 simulate <hand> <bid> <opponents> <seed> <ruleset>"),
+        Some("play") => fail("Usage: aoc7 play <ruleset>"),
+        _ => {
+            part1::solution();
+            part2::solution();
+        }
+    }
 }
@@ -0,0 +1,152 @@
+use std::{cmp::Reverse, collections::HashMap, fmt};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::camel_cards::{Card, Hand, HandKind, Ruleset};
+
+/// Deals random hands under a ruleset. The same seed always deals the same hands.
+pub struct Simulator<'r> {
+    rules: &'r Ruleset,
+    rng: ChaCha8Rng,
+}
+
+/// The shoe runs out before every hand at the table is dealt.
+#[derive(Debug)]
+pub struct NotEnoughCards {
+    pub decks: usize,
+    pub hands: usize,
+}
+
+impl fmt::Display for NotEnoughCards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Not enough cards in {} deck(s) to deal {} hands",
+            self.decks, self.hands
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TournamentResult {
+    pub rounds: usize,
+    /// Rounds where the hand beat every opponent
+    pub wins: usize,
+    /// Sum of the hand's rank in every round, where the weakest hand at the table is rank 1
+    pub total_rank: usize,
+}
+
+impl TournamentResult {
+    pub fn win_probability(&self) -> f64 {
+        self.wins as f64 / self.rounds as f64
+    }
+
+    pub fn expected_rank(&self) -> f64 {
+        self.total_rank as f64 / self.rounds as f64
+    }
+
+    /// A bid pays out its amount times the rank of its hand.
+    pub fn expected_winnings(&self, bid: usize) -> f64 {
+        bid as f64 * self.expected_rank()
+    }
+}
+
+impl<'r> Simulator<'r> {
+    pub fn new(rules: &'r Ruleset, seed: u64) -> Simulator<'r> {
+        Simulator {
+            rules,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Deals `hands` hands. Without a deck limit every card is drawn independently, otherwise
+    /// they all come out of one shuffled shoe that is missing the `excluded` cards.
+    pub fn deal(
+        &mut self,
+        hands: usize,
+        excluded: &[Card],
+    ) -> Result<Vec<Hand<'r>>, NotEnoughCards> {
+        let deck = self.rules.deck();
+        let hand_size = self.rules.hand_size;
+
+        let cards: Vec<Card> = match self.rules.decks {
+            None => (0..hands * hand_size)
+                .map(|_| *deck.choose(&mut self.rng).unwrap())
+                .collect(),
+            Some(decks) => {
+                let mut shoe: Vec<Card> = deck.repeat(decks);
+                for card in excluded {
+                    let position = shoe.iter().position(|other| other == card).unwrap();
+                    shoe.swap_remove(position);
+                }
+
+                if shoe.len() < hands * hand_size {
+                    return Err(NotEnoughCards { decks, hands });
+                }
+
+                shoe.partial_shuffle(&mut self.rng, hands * hand_size)
+                    .0
+                    .to_vec()
+            }
+        };
+
+        Ok(cards
+            .chunks(hand_size)
+            .map(|cards| Hand {
+                cards: cards.to_vec(),
+                rules: self.rules,
+            })
+            .collect())
+    }
+
+    /// How often every kind of hand comes up, strongest kind first. Every sample is dealt from a
+    /// full shoe.
+    pub fn kind_frequencies(
+        &mut self,
+        samples: usize,
+    ) -> Result<Vec<(HandKind, usize)>, NotEnoughCards> {
+        let mut frequencies: HashMap<HandKind, usize> = HashMap::new();
+
+        for _ in 0..samples {
+            for hand in self.deal(1, &[])? {
+                *frequencies.entry(hand.kind()).or_default() += 1;
+            }
+        }
+
+        let mut frequencies: Vec<(HandKind, usize)> = frequencies.into_iter().collect();
+        frequencies.sort_by_key(|(kind, _)| Reverse(*kind));
+        Ok(frequencies)
+    }
+
+    /// Plays `rounds` rounds of `hand` against `opponents` freshly dealt hands. Only the puzzle
+    /// rules decide who is ahead, so an opponent with an equally strong hand doesn't count as
+    /// beaten.
+    pub fn tournament(
+        &mut self,
+        hand: &Hand<'r>,
+        opponents: usize,
+        rounds: usize,
+    ) -> Result<TournamentResult, NotEnoughCards> {
+        let mut result = TournamentResult {
+            rounds,
+            ..Default::default()
+        };
+
+        let key = hand.sort_key();
+        for _ in 0..rounds {
+            let table = self.deal(opponents, &hand.cards)?;
+            let weaker = table
+                .iter()
+                .filter(|opponent| opponent.sort_key() < key)
+                .count();
+
+            if weaker == opponents {
+                result.wins += 1;
+            }
+            result.total_rank += weaker + 1;
+        }
+
+        Ok(result)
+    }
+}
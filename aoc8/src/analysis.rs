@@ -0,0 +1,263 @@
+use std::fmt;
+
+use crate::ghost::GhostPath;
+use crate::network::{Instruction, Network, NodeId};
+
+const INSTRUCTIONS: [Instruction; 2] = [Instruction::Left, Instruction::Right];
+
+/// Longest list of labels shown for any one line of the report
+const MAX_LABELS: usize = 12;
+
+/// The shape of a network, to see why the LCM shortcut does or doesn't work for it.
+pub struct Report<'n> {
+    network: &'n Network,
+    components: Vec<Vec<NodeId>>,
+    self_loops: Vec<(NodeId, Instruction)>,
+    unreachable: Vec<NodeId>,
+    ghosts: Vec<GhostReport>,
+}
+
+struct GhostReport {
+    start: NodeId,
+    path: GhostPath,
+    /// Every node on the cycle, in the order the ghost first visits them
+    cycle_nodes: Vec<NodeId>,
+}
+
+impl GhostReport {
+    /// Whether the ghost is on a goal exactly at every multiple of its cycle length, which is
+    /// what taking the LCM of the first arrivals assumes.
+    fn fits_lcm(&self) -> bool {
+        self.path.tail_goals.is_empty() && self.path.cycle_goals == [self.path.cycle]
+    }
+}
+
+impl<'n> Report<'n> {
+    pub fn new(
+        network: &'n Network,
+        instructions: &[Instruction],
+        starts: &[NodeId],
+        is_goal: impl Fn(NodeId) -> bool,
+    ) -> Report<'n> {
+        let self_loops = network
+            .nodes()
+            .flat_map(|node| INSTRUCTIONS.map(|instruction| (node, instruction)))
+            .filter(|(node, instruction)| network.next(*node, *instruction) == *node)
+            .collect();
+
+        // Anything a ghost can get to by some choice of directions
+        let mut reachable = vec![false; network.len()];
+        let mut stack = starts.to_vec();
+        for start in starts {
+            reachable[*start as usize] = true;
+        }
+        while let Some(node) = stack.pop() {
+            for instruction in INSTRUCTIONS {
+                let next = network.next(node, instruction);
+                if !reachable[next as usize] {
+                    reachable[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let ghosts = starts
+            .iter()
+            .map(|start| {
+                let path = GhostPath::trace(network, instructions, *start, &is_goal);
+
+                let mut current_node = *start;
+                let mut on_cycle = vec![false; network.len()];
+                let mut cycle_nodes = vec![];
+                for step in 0..path.cycle_end() {
+                    if step >= path.tail && !on_cycle[current_node as usize] {
+                        on_cycle[current_node as usize] = true;
+                        cycle_nodes.push(current_node);
+                    }
+                    let instruction = instructions[step as usize % instructions.len()];
+                    current_node = network.next(current_node, instruction);
+                }
+
+                GhostReport {
+                    start: *start,
+                    path,
+                    cycle_nodes,
+                }
+            })
+            .collect();
+
+        Report {
+            network,
+            components: strongly_connected_components(network),
+            self_loops,
+            unreachable: network
+                .nodes()
+                .filter(|node| !reachable[*node as usize])
+                .collect(),
+            ghosts,
+        }
+    }
+
+    fn labels(&self, nodes: &[NodeId]) -> String {
+        if nodes.is_empty() {
+            return "none".to_owned();
+        }
+
+        let mut labels: Vec<&str> = nodes
+            .iter()
+            .take(MAX_LABELS)
+            .map(|node| self.network.label(*node))
+            .collect();
+
+        let more = format!("and {} more", nodes.len().saturating_sub(MAX_LABELS));
+        if nodes.len() > MAX_LABELS {
+            labels.push(&more);
+        }
+
+        labels.join(", ")
+    }
+
+    fn is_cyclic(&self, component: &[NodeId]) -> bool {
+        component.len() > 1
+            || self
+                .self_loops
+                .iter()
+                .any(|(node, _)| *node == component[0])
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} nodes in {} strongly connected components",
+            self.network.len(),
+            self.components.len()
+        )?;
+
+        writeln!(f, "Components with a cycle:")?;
+        for component in self.components.iter().filter(|c| self.is_cyclic(c)) {
+            writeln!(f, "  {} nodes: {}", component.len(), self.labels(component))?;
+        }
+
+        let self_loops: Vec<String> = self
+            .self_loops
+            .iter()
+            .map(|(node, instruction)| {
+                let direction = match instruction {
+                    Instruction::Left => "L",
+                    Instruction::Right => "R",
+                };
+                format!("{} ({direction})", self.network.label(*node))
+            })
+            .collect();
+        match self_loops.is_empty() {
+            true => writeln!(f, "Self-loops: none")?,
+            false => writeln!(f, "Self-loops: {}", self_loops.join(", "))?,
+        }
+
+        writeln!(
+            f,
+            "Unreachable from any start: {}",
+            self.labels(&self.unreachable)
+        )?;
+
+        writeln!(f, "Ghosts:")?;
+        for ghost in &self.ghosts {
+            let path = &ghost.path;
+            writeln!(
+                f,
+                "  {}: enters a cycle of {} steps after {} steps, through {}",
+                self.network.label(ghost.start),
+                path.cycle,
+                path.tail,
+                self.labels(&ghost.cycle_nodes)
+            )?;
+            writeln!(
+                f,
+                "    on a goal at steps {:?}, then {:?} + {}k{}",
+                path.tail_goals,
+                path.cycle_goals,
+                path.cycle,
+                if ghost.fits_lcm() { " (fits LCM)" } else { "" }
+            )?;
+        }
+
+        writeln!(
+            f,
+            "LCM of first arrivals is the answer: {}",
+            match self.ghosts.iter().all(|ghost| ghost.fits_lcm()) {
+                true => "yes",
+                false => "not necessarily",
+            }
+        )
+    }
+}
+
+/// Tarjan's algorithm, with an explicit stack so deep networks can't overflow the real one.
+fn strongly_connected_components(network: &Network) -> Vec<Vec<NodeId>> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut index = vec![UNVISITED; network.len()];
+    let mut lowlink = vec![0; network.len()];
+    let mut on_stack = vec![false; network.len()];
+    let mut stack: Vec<NodeId> = vec![];
+    let mut components: Vec<Vec<NodeId>> = vec![];
+    let mut counter = 0;
+
+    for root in network.nodes() {
+        if index[root as usize] != UNVISITED {
+            continue;
+        }
+
+        index[root as usize] = counter;
+        lowlink[root as usize] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root as usize] = true;
+
+        // Each frame is a node and how many of its edges have been followed so far
+        let mut calls: Vec<(NodeId, usize)> = vec![(root, 0)];
+
+        while let Some((node, edge)) = calls.last_mut() {
+            let node = *node;
+
+            if let Some(instruction) = INSTRUCTIONS.get(*edge) {
+                *edge += 1;
+                let next = network.next(node, *instruction);
+
+                if index[next as usize] == UNVISITED {
+                    index[next as usize] = counter;
+                    lowlink[next as usize] = counter;
+                    counter += 1;
+                    stack.push(next);
+                    on_stack[next as usize] = true;
+                    calls.push((next, 0));
+                } else if on_stack[next as usize] {
+                    lowlink[node as usize] = lowlink[node as usize].min(index[next as usize]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if let Some((parent, _)) = calls.last() {
+                lowlink[*parent as usize] = lowlink[*parent as usize].min(lowlink[node as usize]);
+            }
+
+            if lowlink[node as usize] == index[node as usize] {
+                let mut component = vec![];
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member as usize] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
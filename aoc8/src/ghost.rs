@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::math::crt;
+use crate::network::{Instruction, Network, NodeId};
+
+/// Where one ghost goes, as a tail followed by a cycle that repeats forever. The state that
+/// repeats is the node together with the position in the instructions, so the cycle is exact
+/// for any input.
+#[derive(Debug)]
+pub struct GhostPath {
+    /// Steps taken before the ghost first enters its cycle
+    pub tail: u64,
+    /// Steps it takes to come back around the cycle
+    pub cycle: u64,
+    /// Steps before the cycle at which the ghost stood on a goal
+    pub tail_goals: Vec<u64>,
+    /// Steps at which the ghost stands on a goal during its first pass of the cycle, so every
+    /// one of these plus any multiple of `cycle` is a goal too
+    pub cycle_goals: Vec<u64>,
+}
+
+impl GhostPath {
+    pub fn trace(
+        network: &Network,
+        instructions: &[Instruction],
+        start: NodeId,
+        is_goal: impl Fn(NodeId) -> bool,
+    ) -> GhostPath {
+        let mut seen: HashMap<(NodeId, usize), u64> = HashMap::new();
+        let mut goals: Vec<u64> = vec![];
+        let mut current_node = start;
+        let mut step: u64 = 0;
+
+        loop {
+            let instruction_idx = step as usize % instructions.len();
+
+            if let Some(&cycle_start) = seen.get(&(current_node, instruction_idx)) {
+                let split = goals.partition_point(|goal| *goal < cycle_start);
+
+                return GhostPath {
+                    tail: cycle_start,
+                    cycle: step - cycle_start,
+                    cycle_goals: goals.split_off(split),
+                    tail_goals: goals,
+                };
+            }
+            seen.insert((current_node, instruction_idx), step);
+
+            if is_goal(current_node) {
+                goals.push(step);
+            }
+
+            current_node = network.next(current_node, instructions[instruction_idx]);
+            step += 1;
+        }
+    }
+
+    pub fn first_goal(&self) -> Option<u64> {
+        self.tail_goals
+            .first()
+            .or(self.cycle_goals.first())
+            .copied()
+    }
+
+    /// The step at which the ghost is first back where its cycle started.
+    pub fn cycle_end(&self) -> u64 {
+        self.tail + self.cycle
+    }
+
+    pub fn is_goal_at(&self, step: u64) -> bool {
+        if step < self.tail {
+            self.tail_goals.binary_search(&step).is_ok()
+        } else {
+            let in_cycle = self.tail + (step - self.tail) % self.cycle;
+            self.cycle_goals.binary_search(&in_cycle).is_ok()
+        }
+    }
+}
+
+/// The first step at which every ghost stands on a goal at once, or `None` if that never
+/// happens. With enough ghosts on co-prime cycles this easily outgrows any fixed size integer.
+pub fn first_simultaneous_goal(paths: &[GhostPath]) -> Option<BigUint> {
+    let longest_tail = paths.iter().map(|path| path.tail).max()?;
+    let longest_cycle = paths.iter().map(|path| path.cycle).max()?;
+
+    // Ghosts with plenty of goals tend to line up early, and then walking them all in lockstep
+    // for a while is much cheaper than combining every one of their goals
+    let lockstep = (0..longest_tail + longest_cycle)
+        .find(|step| paths.iter().all(|path| path.is_goal_at(*step)));
+    if let Some(step) = lockstep {
+        return Some(step.into());
+    }
+
+    // After that every ghost is on a goal exactly when the step is one of its cycle goals modulo
+    // its cycle length, so combine every choice of goals with the Chinese remainder theorem.
+    // Starting with the fewest goals keeps the number of combinations down for as long as
+    // possible, and every combination shares one modulus, so repeated residues can be dropped.
+    let mut paths: Vec<&GhostPath> = paths.iter().collect();
+    paths.sort_by_key(|path| path.cycle_goals.len());
+
+    let mut modulus = BigInt::one();
+    let mut residues: Vec<BigInt> = vec![BigInt::zero()];
+    for path in paths {
+        let cycle = BigInt::from(path.cycle);
+
+        residues = residues
+            .iter()
+            .flat_map(|residue| {
+                path.cycle_goals
+                    .iter()
+                    .filter_map(|goal| crt(residue, &modulus, &BigInt::from(*goal), &cycle))
+                    .map(|(residue, _)| residue)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        residues.sort();
+        residues.dedup();
+
+        modulus = modulus.lcm(&cycle);
+    }
+
+    let longest_tail = BigInt::from(longest_tail);
+    residues
+        .into_iter()
+        .map(|residue| {
+            // Smallest step that is at least the longest tail and has the right residue
+            &longest_tail + (residue - &longest_tail).mod_floor(&modulus)
+        })
+        .min()
+        .and_then(|step| step.to_biguint())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::{naive_walk, random_network, Rng};
+    use crate::walk::{NodePattern, Walk};
+
+    #[test]
+    fn trace_matches_walk() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..400 {
+            let (instructions, network) = random_network(&mut rng);
+            let is_goal = |node| network.label(node).ends_with('Z');
+
+            for start in network.nodes() {
+                let path = GhostPath::trace(&network, &instructions, start, is_goal);
+                let goals: Vec<bool> = naive_walk(&network, &instructions, start)
+                    .take(200)
+                    .map(is_goal)
+                    .collect();
+
+                for (step, goal) in goals.iter().enumerate() {
+                    assert_eq!(path.is_goal_at(step as u64), *goal, "{network:?}");
+                }
+                assert_eq!(
+                    path.first_goal(),
+                    goals.iter().position(|goal| *goal).map(|step| step as u64)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn simultaneous_goal_matches_lockstep() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..400 {
+            let (instructions, network) = random_network(&mut rng);
+            let is_goal = |node| network.label(node).ends_with('Z');
+            let starts: Vec<NodeId> = (0..1 + rng.below(3))
+                .map(|_| rng.below(network.len() as u64) as NodeId)
+                .collect();
+
+            let paths: Vec<GhostPath> = starts
+                .iter()
+                .map(|start| GhostPath::trace(&network, &instructions, *start, is_goal))
+                .collect();
+
+            // Cycles are at most 15 steps, so three of them line up again well within this
+            let mut walks: Vec<_> = starts
+                .iter()
+                .map(|start| naive_walk(&network, &instructions, *start))
+                .collect();
+            let expected = (0..5000u32).find(|_| {
+                let nodes: Vec<NodeId> =
+                    walks.iter_mut().map(|walk| walk.next().unwrap()).collect();
+                nodes.into_iter().all(is_goal)
+            });
+
+            assert_eq!(
+                first_simultaneous_goal(&paths),
+                expected.map(BigUint::from),
+                "{network:?} {starts:?}"
+            );
+        }
+    }
+
+    /// Rings of co-prime lengths where every node but the start is a goal. Combining every
+    /// choice of goals would take over 10^11 congruences.
+    #[test]
+    fn rings_full_of_goals() {
+        let mut input = String::new();
+        for (ring, len) in [29, 31, 37, 41, 43, 47].into_iter().enumerate() {
+            let label = |node: usize| {
+                let suffix = if node == 0 { 'A' } else { 'Z' };
+                format!("{ring}{node:02}{suffix}")
+            };
+            for node in 0..len {
+                let next = label((node + 1) % len);
+                input += &format!("{} = ({next}, {next})\n", label(node));
+            }
+        }
+
+        let network: Network = input.as_str().into();
+        let instructions: Vec<Instruction> = "LR".chars().map(|char| char.into()).collect();
+        let walk = Walk::new(&network, &instructions, &"suffix:Z".parse().unwrap()).unwrap();
+        let starts = walk.starts(&NodePattern::Suffix("A".to_owned())).unwrap();
+
+        assert_eq!(starts.len(), 6);
+        assert_eq!(walk.steps_together(&starts).unwrap(), 1u32.into());
+    }
+}
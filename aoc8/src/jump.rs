@@ -0,0 +1,76 @@
+use crate::network::{Instruction, Network, NodeId};
+
+/// What happens from every node over one full pass of the instructions.
+pub struct PassTable {
+    /// Number of steps in a pass
+    pub len: u64,
+    /// The node each pass ends on, indexed by the node it started from
+    pub end: Vec<NodeId>,
+    /// How many steps into the pass a goal is first reached, if at all
+    pub first_goal: Vec<Option<u32>>,
+}
+
+impl PassTable {
+    pub fn new(
+        network: &Network,
+        instructions: &[Instruction],
+        is_goal: impl Fn(NodeId) -> bool,
+    ) -> PassTable {
+        let (end, first_goal) = network
+            .nodes()
+            .map(|start| {
+                let mut current_node = start;
+                let mut first_goal = None;
+
+                for (step, instruction) in instructions.iter().enumerate() {
+                    if first_goal.is_none() && is_goal(current_node) {
+                        first_goal = Some(step as u32);
+                    }
+                    current_node = network.next(current_node, *instruction);
+                }
+
+                (current_node, first_goal)
+            })
+            .unzip();
+
+        PassTable {
+            len: instructions.len() as u64,
+            end,
+            first_goal,
+        }
+    }
+}
+
+/// Binary lifting over whole passes: `levels[k][node]` is where `node` ends up after `2^k`
+/// passes, so any number of passes takes one lookup per bit.
+pub struct JumpTable {
+    levels: Vec<Vec<NodeId>>,
+}
+
+impl JumpTable {
+    pub fn new(passes: &PassTable) -> JumpTable {
+        let mut levels = vec![passes.end.clone()];
+
+        // Enough levels for any step count that fits in a u64
+        let needed = u64::BITS - (u64::MAX / passes.len).leading_zeros();
+        for _ in 1..needed {
+            let previous = levels.last().unwrap();
+            let next = previous
+                .iter()
+                .map(|node| previous[*node as usize])
+                .collect();
+            levels.push(next);
+        }
+
+        JumpTable { levels }
+    }
+
+    pub fn after_passes(&self, mut node: NodeId, passes: u64) -> NodeId {
+        for (bit, level) in self.levels.iter().enumerate() {
+            if passes >> bit & 1 == 1 {
+                node = level[node as usize];
+            }
+        }
+        node
+    }
+}
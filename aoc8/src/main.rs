@@ -1,139 +1,175 @@
-use std::collections::HashMap;
-
-#[derive(Debug)]
-enum Instruction {
-    Left,
-    Right
-}
-
-impl From<char> for Instruction {
-    fn from(value: char) -> Self {
-        match value {
-            'L' => Self::Left,
-            'R' => Self::Right,
-            _ => panic!("Invalid direction")
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Network(HashMap<String, (String, String)>);
-
-impl Network {
-    pub fn left(&self, node: &String) -> &String {
-        &self.0.get(node).unwrap().0
-    }
-
-    pub fn right(&self, node: &String) -> &String {
-        &self.0.get(node).unwrap().1
-    }
+mod analysis;
+mod ghost;
+mod jump;
+mod math;
+mod network;
+mod visualise;
+mod walk;
+
+use std::fmt::Display;
+
+use analysis::Report;
+use ghost::first_simultaneous_goal;
+use math::lcm_many;
+use network::{Instruction, Network, NodeId};
+use num_bigint::BigUint;
+use walk::{NodePattern, Walk, WalkError};
+
+fn parse_input() -> (Vec<Instruction>, Network) {
+    parse(&std::fs::read_to_string("input").unwrap())
 }
 
-fn parse_input() -> (Vec<Instruction>, String, Network) {
-    let input = std::fs::read_to_string("input").unwrap();
+fn parse(input: &str) -> (Vec<Instruction>, Network) {
     let mut split = input.split("\n\n");
 
     let instructions_str = split.next().unwrap();
     let network_str = split.next().unwrap();
 
-    let nodes_vec: Vec<(String, (String, String))> = network_str.lines().map(|line| {
-        let mut equals_split = line.split(" = ");
-        let key = equals_split.next().unwrap().to_owned();
-        
-        let mut values = equals_split.next().unwrap().split(", ");
-        let left_value = values.next().unwrap().replace("(", "");
-        let right_value = values.next().unwrap().replace(")", "");
-        
-        (key, (left_value, right_value))
-    }).collect();
-
-    let first_node = nodes_vec.first().unwrap().0.clone();
-
-    (instructions_str.chars().map(|char| char.into()).collect(), first_node, Network(nodes_vec.into_iter().collect()))
+    (
+        instructions_str.chars().map(|char| char.into()).collect(),
+        network_str.into(),
+    )
 }
 
 mod part1 {
     use crate::*;
 
-    pub fn solution() {
-        let (instructions, first_node, network) = parse_input();
-        
-        let mut current_node = "AAA".to_owned();
-        let mut instruction_idx = 0;
-
-        while current_node != "ZZZ" {
-            let instruction = &instructions[instruction_idx % instructions.len()]; 
+    fn steps(instructions: &[Instruction], network: &Network) -> Result<u64, WalkError> {
+        let walk = Walk::new(network, instructions, &NodePattern::Exact("ZZZ".to_owned()))?;
+        let start = walk.starts(&NodePattern::Exact("AAA".to_owned()))?[0];
 
-            current_node = match instruction {
-                Instruction::Left => network.left(&current_node),
-                Instruction::Right => network.right(&current_node),
-            }.clone();
+        walk.steps_from(start)
+    }
 
-            instruction_idx += 1;
+    pub fn solution() {
+        let (instructions, network) = parse_input();
 
+        match steps(&instructions, &network) {
+            Ok(steps) => println!("(Part 1) Steps required: {steps}"),
+            Err(err) => println!("(Part 1) {err}"),
         }
-
-        println!("(Part 1) Steps required: {instruction_idx}");
     }
 }
 mod part2 {
     use crate::*;
 
-    fn gcd(mut a: usize, mut b: usize) -> usize {
-        while b != 0 {
-            let temp = b;
-            b = a % b;
-            a = temp;
-        }
-        a
-    }
-
-    fn lcm(a: usize, b: usize) -> usize {
-        (a * b) / gcd(a, b)
-    }
-
-    fn lcm_many(factors: Vec<usize>) -> usize {
-        let mut factors = factors.into_iter();
-        let mut result = factors.next().unwrap();
+    fn steps(instructions: &[Instruction], network: &Network) -> Result<BigUint, WalkError> {
+        let walk = Walk::new(network, instructions, &NodePattern::Suffix("Z".to_owned()))?;
+        let paths = walk.ghost_paths(&walk.starts(&NodePattern::Suffix("A".to_owned()))?)?;
 
-        while let Some(factor) = factors.next() {
-            result = lcm(result, factor);
+        // The usual shortcut, which is only right when every ghost loops back to the start of
+        // its path as soon as it reaches a Z node
+        let distances: Vec<u64> = paths.iter().filter_map(|path| path.first_goal()).collect();
+        if let Some(lcm) = lcm_many(&distances) {
+            println!("(Part 2) LCM of first arrivals: {lcm}");
         }
 
-        result
+        first_simultaneous_goal(&paths).ok_or(WalkError::NeverTogether)
     }
 
     pub fn solution() {
-        let (instructions, first_node, network) = parse_input();
-        
-        let starting_nodes = network.0.keys().filter(|key| key.chars().last() == Some('A'));
-        let mut distances: Vec<usize> = vec![];
+        let (instructions, network) = parse_input();
 
-        for start in starting_nodes {
-            let mut current_node = start.clone();
-            let mut instruction_idx = 0;
-
-            while current_node.chars().last() != Some('Z') {
-                let instruction = &instructions[instruction_idx % instructions.len()]; 
+        match steps(&instructions, &network) {
+            Ok(steps) => println!("(Part 2) Steps required: {steps}"),
+            Err(err) => println!("(Part 2) {err}"),
+        }
+    }
+}
 
-                current_node = match instruction {
-                    Instruction::Left => network.left(&current_node),
-                    Instruction::Right => network.right(&current_node),
-                }.clone();
+fn fail(err: impl Display) -> ! {
+    eprintln!("{err}");
+    std::process::exit(1)
+}
 
-                instruction_idx += 1;
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // A pattern is exact:AAA, suffix:Z, regex:^1.A$ or set:AAA,BBB, and they default to the part 2
+    // starts and goals
+    let pattern = |index: usize, default: &str| -> NodePattern {
+        args.get(index)
+            .map_or(default, |arg| arg.as_str())
+            .parse()
+            .unwrap_or_else(|err| fail(err))
+    };
+    let start = || pattern(1, "suffix:A");
+    let goal = || pattern(2, "suffix:Z");
+
+    // Usage: aoc8 [walk | report | dot] [start pattern] [goal pattern]
+    match args.first().map(|arg| arg.as_str()) {
+        Some("walk") => {
+            let (instructions, network) = parse_input();
+
+            let walk = Walk::new(&network, &instructions, &goal()).unwrap_or_else(|err| fail(err));
+            let starts = walk.starts(&start()).unwrap_or_else(|err| fail(err));
+
+            for start in &starts {
+                match walk.steps_from(*start) {
+                    Ok(steps) => println!("{}: {steps} steps", network.label(*start)),
+                    Err(err) => println!("{}: {err}", network.label(*start)),
+                }
             }
 
-            distances.push(instruction_idx);
+            match walk.steps_together(&starts) {
+                Ok(steps) => println!("All together: {steps} steps"),
+                Err(err) => println!("All together: {err}"),
+            }
+        }
+        // Usage: aoc8 at <steps> [start pattern] [goal pattern]
+        Some("at") => {
+            let (instructions, network) = parse_input();
+            let steps = args
+                .get(1)
+                .and_then(|steps| steps.parse::<u64>().ok())
+                .unwrap_or_else(|| fail("Usage: aoc8 at <steps> [start pattern] [goal pattern]"));
+
+            let walk = Walk::new(&network, &instructions, &pattern(3, "suffix:Z"))
+                .unwrap_or_else(|err| fail(err));
+            let starts = walk
+                .starts(&pattern(2, "suffix:A"))
+                .unwrap_or_else(|err| fail(err));
+
+            for start in starts {
+                let node = walk.position_after(start, steps);
+                println!(
+                    "{} is at {} after {steps} steps{}",
+                    network.label(start),
+                    network.label(node),
+                    if walk.is_goal(node) { " (a goal)" } else { "" }
+                );
+            }
+        }
+        Some("report") => {
+            let (instructions, network) = parse_input();
+            let (start, goal) = (start(), goal());
+
+            let starts: Vec<NodeId> = network
+                .nodes()
+                .filter(|node| start.matches(network.label(*node)))
+                .collect();
+            let report = Report::new(&network, &instructions, &starts, |node| {
+                goal.matches(network.label(node))
+            });
+
+            print!("{report}");
+        }
+        // Usage: aoc8 dot [start pattern] [goal pattern] | dot -Tsvg > network.svg
+        Some("dot") => {
+            let (_, network) = parse_input();
+            let (start, goal) = (start(), goal());
+
+            print!(
+                "{}",
+                network.to_dot(
+                    |node| start.matches(network.label(node)),
+                    |node| goal.matches(network.label(node))
+                )
+            );
+        }
+        _ => {
+            part1::solution();
+            part2::solution();
         }
-
-        let lcm: usize = lcm_many(distances);
-        println!("(Part 2) LCM: {}", lcm);
-
     }
 }
-
-fn main() {
-    part1::solution();
-    part2::solution();
-}
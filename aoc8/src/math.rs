@@ -0,0 +1,128 @@
+use std::fmt;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::{ExtendedGcd, Integer};
+use num_traits::Zero;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let temp = b;
+        b = a % b;
+        a = temp;
+    }
+    a
+}
+
+/// Divides before multiplying, so this only fails when the LCM itself doesn't fit.
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lcm {
+    Fits(u64),
+    /// Only used once the LCM no longer fits in 64 bits
+    Big(BigUint),
+}
+
+impl fmt::Display for Lcm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fits(lcm) => write!(f, "{lcm}"),
+            Self::Big(lcm) => write!(f, "{lcm}"),
+        }
+    }
+}
+
+/// The LCM of every factor, or `None` if there aren't any.
+pub fn lcm_many(factors: &[u64]) -> Option<Lcm> {
+    let (first, rest) = factors.split_first()?;
+    let mut result = *first;
+
+    for (i, factor) in rest.iter().enumerate() {
+        match checked_lcm(result, *factor) {
+            Some(lcm) => result = lcm,
+            None => {
+                // Carry on with big integers from the factor that didn't fit
+                let lcm = rest[i..].iter().fold(BigUint::from(result), |lcm, factor| {
+                    lcm.lcm(&BigUint::from(*factor))
+                });
+                return Some(Lcm::Big(lcm));
+            }
+        }
+    }
+
+    Some(Lcm::Fits(result))
+}
+
+/// Combines `x = a1 (mod m1)` and `x = a2 (mod m2)` into one congruence, even when the moduli
+/// share factors. Returns `None` when the two can't both hold.
+pub fn crt(a1: &BigInt, m1: &BigInt, a2: &BigInt, m2: &BigInt) -> Option<(BigInt, BigInt)> {
+    // x * m1 + y * m2 = g
+    let ExtendedGcd { gcd: g, x, .. } = m1.extended_gcd(m2);
+    let difference = a2 - a1;
+
+    if !(&difference % &g).is_zero() {
+        return None;
+    }
+
+    // a1 + m1 * k is the answer, where m1 * k = a2 - a1 (mod m2)
+    let k = (difference / &g * x).mod_floor(&(m2 / &g));
+    let modulus = m1 / &g * m2;
+
+    Some(((a1 + m1 * k).mod_floor(&modulus), modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::Rng;
+
+    #[test]
+    fn crt_matches_brute_force() {
+        for m1 in 1..=12u64 {
+            for m2 in 1..=12u64 {
+                for a1 in 0..m1 {
+                    for a2 in 0..m2 {
+                        let expected = (0..m1 * m2)
+                            .find(|x| x % m1 == a1 && x % m2 == a2)
+                            .map(|x| (x.into(), BigInt::from(m1 / gcd(m1, m2) * m2)));
+
+                        let [a1, m1, a2, m2] = [a1, m1, a2, m2].map(BigInt::from);
+                        assert_eq!(crt(&a1, &m1, &a2, &m2), expected, "{a1} {m1} {a2} {m2}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lcm_many_matches_big_integers() {
+        let mut rng = Rng(0x1234_5678_9abc_def1);
+        assert_eq!(lcm_many(&[]), None);
+
+        for _ in 0..1000 {
+            // Mostly small factors that share primes, now and then one near the top of u64
+            let factors: Vec<u64> = (0..1 + rng.below(6))
+                .map(|_| match rng.below(4) {
+                    0 => u64::MAX - rng.below(1000),
+                    _ => 1 + rng.below(5000),
+                })
+                .collect();
+
+            let expected = factors.iter().fold(BigUint::from(1u32), |lcm, factor| {
+                lcm.lcm(&BigUint::from(*factor))
+            });
+            let expected = match u64::try_from(&expected) {
+                Ok(lcm) => Lcm::Fits(lcm),
+                Err(_) => Lcm::Big(expected),
+            };
+
+            assert_eq!(lcm_many(&factors), Some(expected), "{factors:?}");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+pub type NodeId = u32;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
+    Left,
+    Right,
+}
+
+impl From<char> for Instruction {
+    fn from(value: char) -> Self {
+        match value {
+            'L' => Self::Left,
+            'R' => Self::Right,
+            _ => panic!("Invalid direction"),
+        }
+    }
+}
+
+/// The nodes are interned into dense ids when parsed, so walking the network never touches a
+/// `String`. The labels are only kept around for display.
+#[derive(Debug)]
+pub struct Network {
+    labels: Vec<String>,
+    /// `[left, right]` for every node, indexed by id
+    edges: Vec<[NodeId; 2]>,
+}
+
+impl Network {
+    pub fn label(&self, node: NodeId) -> &str {
+        &self.labels[node as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// All node ids, in the order they first appear in the input.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len() as NodeId
+    }
+
+    pub fn next(&self, node: NodeId, instruction: Instruction) -> NodeId {
+        self.edges[node as usize][instruction as usize]
+    }
+}
+
+impl From<&str> for Network {
+    fn from(input: &str) -> Self {
+        let mut labels: Vec<String> = vec![];
+        let mut ids: HashMap<String, NodeId> = HashMap::new();
+        let mut edges: Vec<Option<[NodeId; 2]>> = vec![];
+
+        // Nodes can be mentioned as a neighbour before their own line
+        let mut intern = |label: &str, edges: &mut Vec<Option<[NodeId; 2]>>| {
+            *ids.entry(label.to_owned()).or_insert_with(|| {
+                labels.push(label.to_owned());
+                edges.push(None);
+                (labels.len() - 1) as NodeId
+            })
+        };
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, values) = line
+                .split_once(" = ")
+                .unwrap_or_else(|| panic!("Invalid network line: {line}"));
+            let (left, right) = values
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .split_once(", ")
+                .unwrap_or_else(|| panic!("Invalid network line: {line}"));
+
+            let node = intern(key, &mut edges);
+            let left = intern(left, &mut edges);
+            let right = intern(right, &mut edges);
+
+            edges[node as usize] = Some([left, right]);
+        }
+
+        let edges = edges
+            .iter()
+            .enumerate()
+            .map(|(node, edges)| {
+                edges.unwrap_or_else(|| panic!("Node {} has no line of its own", labels[node]))
+            })
+            .collect();
+
+        Network { labels, edges }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A small xorshift generator, so random networks don't need a dependency.
+    pub struct Rng(pub u64);
+
+    impl Rng {
+        pub fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    /// Instructions and a network of at most 5 nodes, where the labels ending in Z are goals.
+    /// Small enough that every walk repeats within a few dozen steps.
+    pub fn random_network(rng: &mut Rng) -> (Vec<Instruction>, Network) {
+        let instructions = (0..1 + rng.below(3))
+            .map(|_| match rng.below(2) {
+                0 => Instruction::Left,
+                _ => Instruction::Right,
+            })
+            .collect();
+
+        let nodes = 1 + rng.below(5);
+        let labels: Vec<String> = (0..nodes)
+            .map(|node| match rng.below(3) {
+                0 => format!("N{node}Z"),
+                _ => format!("N{node}"),
+            })
+            .collect();
+
+        let input: String = labels
+            .iter()
+            .map(|label| {
+                let left = &labels[rng.below(nodes) as usize];
+                let right = &labels[rng.below(nodes) as usize];
+                format!("{label} = ({left}, {right})\n")
+            })
+            .collect();
+
+        (instructions, input.as_str().into())
+    }
+
+    /// Every node a walk from `start` stands on, one step at a time.
+    pub fn naive_walk<'a>(
+        network: &'a Network,
+        instructions: &'a [Instruction],
+        start: NodeId,
+    ) -> impl Iterator<Item = NodeId> + 'a {
+        let mut step = 0;
+        std::iter::successors(Some(start), move |node| {
+            let next = network.next(*node, instructions[step % instructions.len()]);
+            step += 1;
+            Some(next)
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::Write;
+
+use crate::network::{Instruction, Network, NodeId};
+
+impl Network {
+    /// Renders the network as a Graphviz digraph, with left edges solid blue, right edges dashed
+    /// red, start nodes green and goal nodes orange.
+    pub fn to_dot(
+        &self,
+        is_start: impl Fn(NodeId) -> bool,
+        is_goal: impl Fn(NodeId) -> bool,
+    ) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph network {{").unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+
+        for node in self.nodes() {
+            let fill = match (is_start(node), is_goal(node)) {
+                (true, true) => "gold",
+                (true, false) => "palegreen",
+                (false, true) => "orange",
+                (false, false) => continue,
+            };
+            writeln!(
+                dot,
+                r#"    "{}" [style=filled, fillcolor={fill}];"#,
+                self.label(node)
+            )
+            .unwrap();
+        }
+
+        for node in self.nodes() {
+            for (instruction, style) in [
+                (Instruction::Left, "label=L, color=blue"),
+                (Instruction::Right, "label=R, color=red, style=dashed"),
+            ] {
+                writeln!(
+                    dot,
+                    r#"    "{}" -> "{}" [{style}];"#,
+                    self.label(node),
+                    self.label(self.next(node, instruction))
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
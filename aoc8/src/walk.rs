@@ -0,0 +1,342 @@
+use std::{cell::OnceCell, collections::HashSet, fmt, str::FromStr};
+
+use num_bigint::BigUint;
+use regex::Regex;
+
+use crate::ghost::{first_simultaneous_goal, GhostPath};
+use crate::jump::{JumpTable, PassTable};
+use crate::network::{Instruction, Network, NodeId};
+
+/// Picks out start or goal nodes by their label.
+#[derive(Debug)]
+pub enum NodePattern {
+    Exact(String),
+    Suffix(String),
+    Regex(Regex),
+    Set(HashSet<String>),
+}
+
+impl NodePattern {
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            Self::Exact(exact) => label == exact,
+            Self::Suffix(suffix) => label.ends_with(suffix.as_str()),
+            Self::Regex(regex) => regex.is_match(label),
+            Self::Set(set) => set.contains(label),
+        }
+    }
+}
+
+impl fmt::Display for NodePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(exact) => write!(f, "{exact}"),
+            Self::Suffix(suffix) => write!(f, "*{suffix}"),
+            Self::Regex(regex) => write!(f, "/{regex}/"),
+            Self::Set(set) => {
+                let mut labels: Vec<&str> = set.iter().map(|label| label.as_str()).collect();
+                labels.sort();
+                write!(f, "{{{}}}", labels.join(", "))
+            }
+        }
+    }
+}
+
+/// `exact:AAA`, `suffix:Z`, `regex:^1.A$` or `set:AAA,BBB`.
+impl FromStr for NodePattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        match pattern.split_once(':') {
+            Some(("exact", label)) => Ok(Self::Exact(label.to_owned())),
+            Some(("suffix", suffix)) => Ok(Self::Suffix(suffix.to_owned())),
+            Some(("regex", regex)) => Regex::new(regex)
+                .map(Self::Regex)
+                .map_err(|err| format!("Invalid regex {regex}: {err}")),
+            Some(("set", labels)) => Ok(Self::Set(
+                labels.split(',').map(|label| label.to_owned()).collect(),
+            )),
+            _ => Err(format!("Invalid node pattern: {pattern}")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WalkError {
+    NoStart(String),
+    NoGoal(String),
+    /// No goal can be reached from the start, whatever the instructions say
+    Unreachable {
+        start: String,
+    },
+    /// A goal can be reached, but following the instructions goes around in circles instead
+    Loops {
+        start: String,
+        steps: u64,
+    },
+    /// Every start reaches a goal, but never all on the same step
+    NeverTogether,
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoStart(pattern) => write!(f, "No node matches the start pattern {pattern}"),
+            Self::NoGoal(pattern) => write!(f, "No node matches the goal pattern {pattern}"),
+            Self::Unreachable { start } => write!(f, "No goal can be reached from {start}"),
+            Self::Loops { start, steps } => write!(
+                f,
+                "Walking from {start} repeats itself after {steps} steps without reaching a goal"
+            ),
+            Self::NeverTogether => write!(f, "The walks never all reach a goal on the same step"),
+        }
+    }
+}
+
+/// Walks through a network towards the nodes matching a goal pattern.
+pub struct Walk<'n> {
+    network: &'n Network,
+    instructions: &'n [Instruction],
+    /// Whether each node is a goal, indexed by id
+    goals: Vec<bool>,
+    passes: PassTable,
+    /// Only built the first time a position far down the walk is asked for
+    jumps: OnceCell<JumpTable>,
+}
+
+impl<'n> Walk<'n> {
+    pub fn new(
+        network: &'n Network,
+        instructions: &'n [Instruction],
+        goal: &NodePattern,
+    ) -> Result<Walk<'n>, WalkError> {
+        let goals: Vec<bool> = network
+            .nodes()
+            .map(|node| goal.matches(network.label(node)))
+            .collect();
+
+        if !goals.contains(&true) {
+            return Err(WalkError::NoGoal(goal.to_string()));
+        }
+
+        let passes = PassTable::new(network, instructions, |node| goals[node as usize]);
+
+        Ok(Walk {
+            network,
+            instructions,
+            goals,
+            passes,
+            jumps: OnceCell::new(),
+        })
+    }
+
+    pub fn is_goal(&self, node: NodeId) -> bool {
+        self.goals[node as usize]
+    }
+
+    pub fn starts(&self, start: &NodePattern) -> Result<Vec<NodeId>, WalkError> {
+        let starts: Vec<NodeId> = self
+            .network
+            .nodes()
+            .filter(|node| start.matches(self.network.label(*node)))
+            .collect();
+
+        match starts.is_empty() {
+            true => Err(WalkError::NoStart(start.to_string())),
+            false => Ok(starts),
+        }
+    }
+
+    /// Steps until the first goal, counting the start itself as step 0.
+    pub fn steps_from(&self, start: NodeId) -> Result<u64, WalkError> {
+        let mut current_node = start;
+
+        // Every pass starts at the beginning of the instructions, so once there have been more
+        // passes than nodes one of them must have repeated
+        for pass in 0..self.network.len() as u64 {
+            if let Some(offset) = self.passes.first_goal[current_node as usize] {
+                return Ok(pass * self.passes.len + offset as u64);
+            }
+            current_node = self.passes.end[current_node as usize];
+        }
+
+        Err(self.walk_error(start, self.trace(start).cycle_end()))
+    }
+
+    /// Where a walk from `start` is after `steps` steps, taking whole passes in one jump each.
+    pub fn position_after(&self, start: NodeId, steps: u64) -> NodeId {
+        let jumps = self.jumps.get_or_init(|| JumpTable::new(&self.passes));
+        let mut current_node = jumps.after_passes(start, steps / self.passes.len);
+
+        for instruction in &self.instructions[..(steps % self.passes.len) as usize] {
+            current_node = self.network.next(current_node, *instruction);
+        }
+
+        current_node
+    }
+
+    /// Traces the walk from every start, which must all be able to reach a goal.
+    pub fn ghost_paths(&self, starts: &[NodeId]) -> Result<Vec<GhostPath>, WalkError> {
+        starts
+            .iter()
+            .map(|start| {
+                let path = self.trace(*start);
+
+                match path.first_goal() {
+                    Some(_) => Ok(path),
+                    None => Err(self.walk_error(*start, path.cycle_end())),
+                }
+            })
+            .collect()
+    }
+
+    fn trace(&self, start: NodeId) -> GhostPath {
+        GhostPath::trace(self.network, self.instructions, start, |node| {
+            self.is_goal(node)
+        })
+    }
+
+    /// Steps until a walk from every start is on a goal at the same time.
+    pub fn steps_together(&self, starts: &[NodeId]) -> Result<BigUint, WalkError> {
+        first_simultaneous_goal(&self.ghost_paths(starts)?).ok_or(WalkError::NeverTogether)
+    }
+
+    /// Tells apart a goal that can't be reached at all from one the instructions never lead to.
+    fn walk_error(&self, start: NodeId, steps: u64) -> WalkError {
+        let start_label = self.network.label(start).to_owned();
+
+        let mut seen = vec![false; self.network.len()];
+        let mut stack = vec![start];
+        seen[start as usize] = true;
+
+        while let Some(node) = stack.pop() {
+            if self.is_goal(node) {
+                return WalkError::Loops {
+                    start: start_label,
+                    steps,
+                };
+            }
+
+            for instruction in [Instruction::Left, Instruction::Right] {
+                let next = self.network.next(node, instruction);
+                if !seen[next as usize] {
+                    seen[next as usize] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        WalkError::Unreachable { start: start_label }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::tests::{naive_walk, random_network, Rng};
+    use crate::parse;
+
+    fn steps(input: &str, start: &str, goal: &str) -> Result<BigUint, WalkError> {
+        let (instructions, network) = parse(input);
+        let walk = Walk::new(&network, &instructions, &goal.parse().unwrap())?;
+        walk.steps_together(&walk.starts(&start.parse().unwrap())?)
+    }
+
+    #[test]
+    fn examples() {
+        let part1 = "RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+        assert_eq!(steps(part1, "exact:AAA", "exact:ZZZ").unwrap(), 2u32.into());
+
+        let repeating = "LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+        assert_eq!(
+            steps(repeating, "exact:AAA", "exact:ZZZ").unwrap(),
+            6u32.into()
+        );
+
+        let part2 = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+        assert_eq!(steps(part2, "suffix:A", "suffix:Z").unwrap(), 6u32.into());
+    }
+
+    #[test]
+    fn steps_from_matches_naive_walk() {
+        let mut rng = Rng(0x0ddb_a11c_afe5_eed5);
+
+        for _ in 0..400 {
+            let (instructions, network) = random_network(&mut rng);
+            let Ok(walk) = Walk::new(&network, &instructions, &NodePattern::Suffix("Z".into()))
+            else {
+                continue;
+            };
+
+            for start in network.nodes() {
+                // A walk repeats within 15 steps, so a goal that isn't reached by then never is
+                let expected = naive_walk(&network, &instructions, start)
+                    .take(100)
+                    .position(|node| walk.is_goal(node));
+
+                assert_eq!(
+                    walk.steps_from(start).ok(),
+                    expected.map(|steps| steps as u64),
+                    "{network:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn position_after_matches_naive_walk() {
+        let mut rng = Rng(0x5eed_f00d_1234_abcd);
+        // Where a walk goes doesn't depend on the goals, as long as there are some
+        let anything: NodePattern = "regex:".parse().unwrap();
+
+        for _ in 0..200 {
+            let (instructions, network) = random_network(&mut rng);
+            let walk = Walk::new(&network, &instructions, &anything).unwrap();
+
+            for start in network.nodes() {
+                let nodes: Vec<NodeId> = naive_walk(&network, &instructions, start)
+                    .take(300)
+                    .collect();
+
+                for (steps, node) in nodes.iter().enumerate() {
+                    assert_eq!(walk.position_after(start, steps as u64), *node);
+                }
+
+                // Far out, the walk is somewhere in its cycle
+                let path = walk.trace(start);
+                for steps in [u64::MAX, u64::MAX - 1, 1 << 40, 12_345_678_901] {
+                    let in_cycle = path.tail + (steps - path.tail) % path.cycle;
+                    assert_eq!(
+                        walk.position_after(start, steps),
+                        nodes[in_cycle as usize],
+                        "{network:?}"
+                    );
+                }
+            }
+        }
+    }
+}
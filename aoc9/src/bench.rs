@@ -0,0 +1,105 @@
+use std::time::Instant;
+
+use crate::differences::Differences;
+use crate::history::{History, HistoryError};
+use crate::reading::Reading;
+
+/// Readings per generated history, like the puzzle input
+const READINGS: i64 = 21;
+
+/// `lines` histories from random polynomials of degree up to 5 with small coefficients. Always the
+/// same ones, so timings can be compared between runs.
+fn generate<T: Reading>(lines: usize) -> Vec<History<T>> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut random = move |below: u64| {
+        // Knuth's MMIX LCG, the high bits are the random ones
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % below
+    };
+
+    (0..lines)
+        .map(|_| {
+            let degree = random(6) as usize;
+            let coefficients: Vec<i64> = (0..=degree).map(|_| random(19) as i64 - 9).collect();
+
+            (0..READINGS)
+                .map(|x| {
+                    let value = coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |value, coefficient| value * x + coefficient);
+                    T::from_i64(value)
+                })
+                .collect::<Vec<_>>()
+        })
+        .map(History)
+        .collect()
+}
+
+/// Next and previous value from a full table of differences, one `Vec` per level.
+fn extrapolate_with_table<T: Reading>(history: &History<T>) -> Result<(T, T), HistoryError> {
+    let overflow = || HistoryError::ExtrapolationOverflow;
+
+    history.get_all_derivatives()?.iter().rev().try_fold(
+        (T::zero(), T::zero()),
+        |(next, previous), derivative| {
+            Ok((
+                derivative
+                    .0
+                    .last()
+                    .unwrap()
+                    .checked_add(&next)
+                    .ok_or_else(overflow)?,
+                derivative.0[0]
+                    .checked_sub(&previous)
+                    .ok_or_else(overflow)?,
+            ))
+        },
+    )
+}
+
+fn add<T: Reading>(sums: (T, T), values: (T, T)) -> Result<(T, T), HistoryError> {
+    let overflow = || HistoryError::ExtrapolationOverflow;
+
+    Ok((
+        sums.0.checked_add(&values.0).ok_or_else(overflow)?,
+        sums.1.checked_add(&values.1).ok_or_else(overflow)?,
+    ))
+}
+
+/// Times both parts with a table of differences per history against one in-place buffer.
+pub fn run<T: Reading>(lines: usize) {
+    let histories = generate::<T>(lines);
+    let zero = || (T::zero(), T::zero());
+
+    let start = Instant::now();
+    let table = histories
+        .iter()
+        .try_fold(zero(), |sums, history| {
+            add(sums, extrapolate_with_table(history)?)
+        })
+        .unwrap_or_else(|err| panic!("{err}"));
+    let table_time = start.elapsed();
+
+    let start = Instant::now();
+    let mut differences = Differences::new();
+    let in_place = histories
+        .iter()
+        .try_fold(zero(), |sums, history| {
+            differences.compute_polynomial(&history.0)?;
+            add(sums, (differences.next()?, differences.previous()?))
+        })
+        .unwrap_or_else(|err| panic!("{err}"));
+    let in_place_time = start.elapsed();
+
+    assert_eq!(
+        table, in_place,
+        "Both ways should extrapolate the same values"
+    );
+
+    println!("{lines} histories: next {}, previous {}", table.0, table.1);
+    println!("Table of differences: {table_time:?}");
+    println!("In place: {in_place_time:?}");
+}
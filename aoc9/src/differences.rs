@@ -0,0 +1,103 @@
+use crate::history::{HistoryError, Shape};
+use crate::reading::Reading;
+
+/// Differences a history in place, one level at a time, keeping only the first and last value of
+/// every level. That's all extrapolating needs, so there's no table of every level. The same one
+/// can be reused for every history, after which nothing is allocated at all.
+#[derive(Debug)]
+pub struct Differences<T> {
+    buffer: Vec<T>,
+    firsts: Vec<T>,
+    lasts: Vec<T>,
+}
+
+impl<T: Reading> Differences<T> {
+    pub fn new() -> Differences<T> {
+        Differences {
+            buffer: vec![],
+            firsts: vec![],
+            lasts: vec![],
+        }
+    }
+
+    /// Differences `readings` down to a row of zeros, or a single value if there isn't one.
+    pub fn compute(&mut self, readings: &[T]) -> Result<Shape, HistoryError> {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(readings);
+        self.firsts.clear();
+        self.lasts.clear();
+
+        if readings.len() < 2 {
+            return Ok(Shape::InsufficientData);
+        }
+
+        let mut len = readings.len();
+        let mut all_zero = readings.iter().all(|reading| reading.is_zero());
+
+        loop {
+            let row = &mut self.buffer[..len];
+            self.firsts.push(row[0].clone());
+            self.lasts.push(row[len - 1].clone());
+
+            if all_zero {
+                // Readings that are all zero are a constant too
+                return Ok(Shape::Polynomial {
+                    degree: self.firsts.len().saturating_sub(2),
+                });
+            }
+            if len == 1 {
+                return Ok(Shape::NotPolynomial);
+            }
+
+            // Each difference only needs the value after it, which hasn't been overwritten yet
+            all_zero = true;
+            for i in 0..len - 1 {
+                row[i] =
+                    row[i + 1]
+                        .checked_sub(&row[i])
+                        .ok_or(HistoryError::DifferenceOverflow {
+                            level: self.firsts.len(),
+                        })?;
+                all_zero &= row[i].is_zero();
+            }
+            len -= 1;
+        }
+    }
+
+    /// Like `compute`, but refuses histories that can't be extrapolated from.
+    pub fn compute_polynomial(&mut self, readings: &[T]) -> Result<(), HistoryError> {
+        match self.compute(readings)? {
+            Shape::Polynomial { .. } => Ok(()),
+            Shape::InsufficientData => Err(HistoryError::InsufficientData {
+                readings: readings.len(),
+            }),
+            Shape::NotPolynomial => Err(HistoryError::NotPolynomial {
+                readings: readings.len(),
+            }),
+        }
+    }
+
+    /// The first value at every level above the row of zeros, starting with the first reading.
+    /// Only meaningful after `compute_polynomial`.
+    pub fn leading(&self) -> &[T] {
+        &self.firsts[..self.firsts.len().saturating_sub(1)]
+    }
+
+    pub fn next(&self) -> Result<T, HistoryError> {
+        self.lasts.iter().rev().try_fold(T::zero(), |below, last| {
+            last.checked_add(&below)
+                .ok_or(HistoryError::ExtrapolationOverflow)
+        })
+    }
+
+    pub fn previous(&self) -> Result<T, HistoryError> {
+        self.firsts
+            .iter()
+            .rev()
+            .try_fold(T::zero(), |below, first| {
+                first
+                    .checked_sub(&below)
+                    .ok_or(HistoryError::ExtrapolationOverflow)
+            })
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::{differences::Differences, polynomial::Polynomial, reading::Reading};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    InvalidReading(String),
+    /// A reading that is a valid integer, but too big for the reading type
+    ReadingOverflow(String),
+    /// A difference between two readings at this level doesn't fit the reading type
+    DifferenceOverflow {
+        level: usize,
+    },
+    /// The extrapolated value, or a sum of them, doesn't fit the reading type
+    ExtrapolationOverflow,
+    /// Too few readings to find any pattern in
+    InsufficientData {
+        readings: usize,
+    },
+    /// The differences never reach a row of zeros
+    NotPolynomial {
+        readings: usize,
+    },
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidReading(reading) => write!(f, "Invalid reading: {reading}"),
+            Self::ReadingOverflow(reading) => {
+                write!(f, "Reading {reading} doesn't fit, try a wider reading type")
+            }
+            Self::DifferenceOverflow { level } => write!(
+                f,
+                "Differences at level {level} overflow, try a wider reading type"
+            ),
+            Self::ExtrapolationOverflow => {
+                write!(f, "Extrapolated value overflows, try a wider reading type")
+            }
+            Self::InsufficientData { readings } => {
+                write!(f, "Can't extrapolate from {readings} reading(s)")
+            }
+            Self::NotPolynomial { readings } => write!(
+                f,
+                "Can't extrapolate, no polynomial of degree below {} fits the {readings} readings",
+                readings - 1
+            ),
+        }
+    }
+}
+
+/// What kind of sequence a history is, going by its differences.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// A row of zeros is reached, so there's at least one reading more than the polynomial needs
+    Polynomial { degree: usize },
+    /// Fewer than two readings, so there are no differences at all
+    InsufficientData,
+    /// The differences run out before reaching a row of zeros. The readings still fit a
+    /// polynomial of degree `len - 1`, but nothing backs that up.
+    NotPolynomial,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Polynomial { degree } => write!(f, "polynomial of degree {degree}"),
+            Self::InsufficientData => write!(f, "insufficient data"),
+            Self::NotPolynomial => write!(f, "not polynomial"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct History<T>(pub Vec<T>);
+
+impl<T: Reading> History<T> {
+    /// `None` if any difference doesn't fit in `T`.
+    pub fn derivative(&self) -> Option<History<T>> {
+        self.0
+            .windows(2) // Split readings into windows of size 2
+            .map(|window| window[1].checked_sub(&window[0])) // Get the difference in all of the windows
+            .collect::<Option<_>>() // Put the resulting values in a Vec
+            .map(History)
+    }
+
+    fn is_all_zero(&self) -> bool {
+        !self.0.is_empty() && self.0.iter().all(|reading| reading.is_zero())
+    }
+
+    /// Every level of differences, down to either a row of zeros or a row with a single value.
+    pub fn get_all_derivatives(&self) -> Result<Vec<History<T>>, HistoryError> {
+        let mut derivatives: Vec<History<T>> = vec![self.clone()];
+
+        loop {
+            let last = derivatives.last().unwrap();
+            if last.0.len() < 2 || last.is_all_zero() {
+                break;
+            }
+
+            let derivative = last.derivative().ok_or(HistoryError::DifferenceOverflow {
+                level: derivatives.len(),
+            })?;
+            derivatives.push(derivative);
+        }
+
+        Ok(derivatives)
+    }
+
+    pub fn shape(&self) -> Result<Shape, HistoryError> {
+        Differences::new().compute(&self.0)
+    }
+
+    /// The first value at every level of differences above the row of zeros, starting with the
+    /// first reading.
+    pub fn leading_differences(&self) -> Result<Vec<T>, HistoryError> {
+        let mut differences = Differences::new();
+        differences.compute_polynomial(&self.0)?;
+
+        Ok(differences.leading().to_vec())
+    }
+
+    /// The value at any position in the sequence, where the readings are at `0..len`, so `len` is
+    /// the next value and `-1` the previous one.
+    ///
+    /// Uses Newton's forward difference formula, `f(k) = sum over j of C(k, j) * differences[j]`,
+    /// instead of extending the difference table one step at a time.
+    pub fn extrapolate(&self, index: i64) -> Result<T, HistoryError> {
+        let overflow = || HistoryError::ExtrapolationOverflow;
+        let index = T::from_i64(index);
+
+        let mut value = T::zero();
+        // C(index, j), which works for negative indices too
+        let mut binomial = T::from_i64(1);
+
+        for (j, difference) in self.leading_differences()?.iter().enumerate() {
+            // C(k, j) = C(k, j - 1) * (k - j + 1) / j, which always divides exactly. Only worked
+            // out when there's a difference to use it on, as it can overflow long before the value
+            if j > 0 {
+                let j = T::from_i64(j as i64);
+                let previous = j.checked_sub(&T::from_i64(1)).ok_or_else(overflow)?;
+                binomial = binomial
+                    .checked_mul(&index.checked_sub(&previous).ok_or_else(overflow)?)
+                    .and_then(|product| product.checked_div(&j))
+                    .ok_or_else(overflow)?;
+            }
+
+            let term = binomial.checked_mul(difference).ok_or_else(overflow)?;
+            value = value.checked_add(&term).ok_or_else(overflow)?;
+        }
+
+        Ok(value)
+    }
+
+    /// The polynomial through every reading, with the first reading at `x = 0`.
+    pub fn polynomial(&self) -> Result<Polynomial, HistoryError> {
+        let differences: Vec<BigInt> = self
+            .leading_differences()?
+            .iter()
+            .map(|difference| difference.to_bigint())
+            .collect();
+
+        Ok(Polynomial::from_differences(&differences))
+    }
+}
+
+impl<T: Reading> TryFrom<&str> for History<T> {
+    type Error = HistoryError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        line.split_ascii_whitespace()
+            .map(|number| {
+                number
+                    .parse::<T>()
+                    .map_err(|_| match number.parse::<BigInt>() {
+                        Ok(_) => HistoryError::ReadingOverflow(number.to_owned()),
+                        Err(_) => HistoryError::InvalidReading(number.to_owned()),
+                    })
+            })
+            .collect::<Result<_, _>>()
+            .map(History)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_rational::BigRational;
+    use num_traits::Zero;
+
+    use super::*;
+
+    /// Readings of random polynomials with small coefficients, with at least one reading more
+    /// than the degree needs.
+    fn random_histories() -> impl Iterator<Item = History<i64>> {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut random = move |below: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % below
+        };
+
+        (0..3000).map(move |_| {
+            let degree = random(6);
+            let coefficients: Vec<i64> = (0..=degree).map(|_| random(21) as i64 - 10).collect();
+            let len = degree as i64 + 2 + random(8) as i64;
+
+            History(
+                (0..len)
+                    .map(|x| {
+                        coefficients
+                            .iter()
+                            .rev()
+                            .fold(0, |value, coefficient| value * x + coefficient)
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn extrapolate_matches_differences() {
+        let mut differences = Differences::new();
+
+        for history in random_histories() {
+            differences.compute_polynomial(&history.0).unwrap();
+            let len = history.0.len() as i64;
+
+            assert_eq!(history.extrapolate(len), differences.next(), "{history:?}");
+            assert_eq!(
+                history.extrapolate(-1),
+                differences.previous(),
+                "{history:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn polynomial_reproduces_readings() {
+        for history in random_histories() {
+            let polynomial = history.polynomial().unwrap();
+
+            for (x, reading) in history.0.iter().enumerate() {
+                let x = BigRational::from_integer(x.into());
+                let value = polynomial
+                    .0
+                    .iter()
+                    .rev()
+                    .fold(BigRational::zero(), |value, coefficient| {
+                        value * &x + coefficient
+                    });
+
+                assert_eq!(
+                    value,
+                    BigRational::from_integer((*reading).into()),
+                    "{history:?}"
+                );
+            }
+        }
+    }
+}
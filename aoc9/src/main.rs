@@ -1,104 +1,148 @@
-#[derive(Debug, Clone)]
-struct History(Vec<isize>);
-
-impl History {
-    pub fn derivative(&self) -> History {
-        History(
-            self.0
-                .windows(2) // Split readings into windows of size 2
-                .map(|window| window[1] - window[0]) // Get the difference in all of the windows
-                .collect(), // Put the resulting values in a Vec
-        )
-    }
-
-    pub fn get_all_derivatives(&self) -> Vec<History> {
-        let mut derivatives: Vec<History> = vec![self.clone()];
-
-        loop {
-            let derivative = match derivatives.last() {
-                Some(derivative) => derivative.derivative(),
-                None => self.derivative(),
-            };
+mod bench;
+mod differences;
+mod history;
+mod polynomial;
+mod reading;
+mod visualise;
+
+use std::{fmt::Display, io::IsTerminal};
+
+use differences::Differences;
+use history::{History, HistoryError, Shape};
+use num_bigint::BigInt;
+use reading::Reading;
+
+/// One history per line, each of which may have failed to parse on its own.
+fn parse_input<T: Reading>() -> Vec<Result<History<T>, HistoryError>> {
+    std::fs::read_to_string("input")
+        .unwrap()
+        .lines()
+        .map(|line| line.try_into())
+        .collect()
+}
 
-            let done = derivative.0.iter().all(|reading| reading == &0);
-            derivatives.push(derivative);
+/// Extrapolates every history and adds the results up, stopping at the first line that fails.
+fn sum_extrapolated<T: Reading>(
+    extrapolate: impl Fn(&Differences<T>) -> Result<T, HistoryError>,
+) -> Result<T, (usize, HistoryError)> {
+    // Every line is differenced in the same buffer
+    let mut differences = Differences::new();
+
+    parse_input()
+        .into_iter()
+        .enumerate()
+        .try_fold(T::zero(), |sum, (i, history)| {
+            history
+                .and_then(|history| {
+                    differences.compute_polynomial(&history.0)?;
+                    sum.checked_add(&extrapolate(&differences)?)
+                        .ok_or(HistoryError::ExtrapolationOverflow)
+                })
+                .map_err(|err| (i + 1, err))
+        })
+}
 
-            if done {
-                break;
-            }
+/// Prints something about every history, or why it couldn't be worked out, line by line.
+fn report<T: Reading, R: Display>(describe: impl Fn(&History<T>) -> Result<R, HistoryError>) {
+    for (i, history) in parse_input::<T>().into_iter().enumerate() {
+        match history.and_then(|history| describe(&history)) {
+            Ok(description) => println!("Line {}: {description}", i + 1),
+            Err(err) => println!("Line {}: {err}", i + 1),
         }
-
-        derivatives
     }
+}
 
-    pub fn extrapolate_next(&self) -> isize {
-        let mut derivatives = self.get_all_derivatives();
-        let mut extrapolated_value = 0;
-
-        while let Some(derivative) = derivatives.pop() {
-            extrapolated_value += derivative.0.last().unwrap();
+mod part1 {
+    use crate::*;
+    pub fn solution<T: Reading>() {
+        match sum_extrapolated::<T>(Differences::next) {
+            Ok(sum) => println!("(Part 1) Sum of extrapolated values: {}", sum),
+            Err((line, err)) => println!("(Part 1) Line {line}: {err}"),
         }
-
-        extrapolated_value
     }
+}
 
-    pub fn extrapolate_previous(&self) -> isize {
-        let mut derivatives = self.get_all_derivatives();
-        let mut extrapolated_value = 0;
-
-        while let Some(derivative) = derivatives.pop() {
-            extrapolated_value = derivative.0.first().unwrap() - extrapolated_value;
+mod part2 {
+    use crate::*;
+    pub fn solution<T: Reading>() {
+        match sum_extrapolated::<T>(Differences::previous) {
+            Ok(sum) => println!("(Part 2) Sum of extrapolated values: {}", sum),
+            Err((line, err)) => println!("(Part 2) Line {line}: {err}"),
         }
-
-        extrapolated_value
     }
 }
 
-impl From<&str> for History {
-    fn from(line: &str) -> Self {
-        Self(
-            line.split_ascii_whitespace()
-                .map(|number| number.parse::<isize>().unwrap())
-                .collect(),
-        )
+fn run<T: Reading>(command: &[String]) {
+    match command.first().map(|arg| arg.as_str()) {
+        Some("at") => {
+            let index = command
+                .get(1)
+                .and_then(|index| index.parse::<i64>().ok())
+                .unwrap_or_else(|| usage());
+            report::<T, T>(|history| history.extrapolate(index));
+        }
+        Some("formula") => report::<T, String>(|history| {
+            history
+                .polynomial()
+                .map(|polynomial| format!("f(x) = {polynomial}"))
+        }),
+        Some("classify") => report::<T, Shape>(|history| history.shape()),
+        Some("bench") => {
+            let lines = command
+                .get(1)
+                .map_or(Some(1_000_000), |lines| lines.parse().ok())
+                .unwrap_or_else(|| usage());
+            bench::run::<T>(lines);
+        }
+        Some("pyramid") => {
+            let format = command.get(1).map_or("text", |format| format.as_str());
+            let line = command
+                .get(2)
+                .map(|line| line.parse::<usize>().unwrap_or_else(|_| usage()));
+
+            for (i, history) in parse_input::<T>().into_iter().enumerate() {
+                if line.is_some_and(|line| line != i + 1) {
+                    continue;
+                }
+
+                println!("Line {}:", i + 1);
+                match history.and_then(|history| history.pyramid()) {
+                    Ok(pyramid) => match format {
+                        "markdown" => println!("{}", pyramid.to_markdown()),
+                        "html" => println!("{}", pyramid.to_html()),
+                        _ => println!("{}", pyramid.to_text(std::io::stdout().is_terminal())),
+                    },
+                    Err(err) => println!("{err}\n"),
+                }
+            }
+        }
+        _ => {
+            part1::solution::<T>();
+            part2::solution::<T>();
+        }
     }
 }
 
-fn parse_input() -> Vec<History> {
-    std::fs::read_to_string("input")
-        .unwrap()
-        .lines()
-        .map(|line| line.into())
-        .collect()
+/// Index 0 is the first reading of each line, and -1 the value before it.
+fn usage() -> ! {
+    eprintln!(
+        "Usage: aoc9 [i64 | i128 | big] [at <index> | formula | classify | bench [lines] \
+         | pyramid [text | markdown | html] [line]]"
+    );
+    std::process::exit(1)
 }
 
-mod part1 {
-    use crate::*;
-    pub fn solution() {
-        let histories = parse_input();
-        let sum: isize = histories
-            .into_iter()
-            .map(|history| history.extrapolate_next())
-            .sum();
-
-        println!("(Part 1) Sum of extrapolated values: {}", sum);
-    }
-}
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-mod part2 {
-    use crate::*;
-    pub fn solution() {
-        let histories = parse_input();
-        let sum: isize = histories
-            .into_iter()
-            .map(|history| history.extrapolate_previous())
-            .sum();
+    let (reading, command) = match args.first().map(|arg| arg.as_str()) {
+        Some(reading @ ("i64" | "i128" | "big")) => (reading, &args[1..]),
+        _ => ("i64", &args[..]),
+    };
 
-        println!("(Part 2) Sum of extrapolated values: {}", sum);
+    match reading {
+        "i128" => run::<i128>(command),
+        "big" => run::<BigInt>(command),
+        _ => run::<i64>(command),
     }
 }
-
-fn main() {
-    part1::solution();
-    part2::solution();
-}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+/// Coefficients of a polynomial, lowest power first. Histories with integer readings can still
+/// need fractional coefficients, like `x(x - 1)/2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial(pub Vec<BigRational>);
+
+impl Polynomial {
+    /// The polynomial through `f(0), f(1), ...` from Newton's forward differences, where
+    /// `differences[j]` is the first value at level `j`:
+    /// `f(x) = sum over j of differences[j] * x(x - 1)...(x - j + 1) / j!`
+    pub fn from_differences(differences: &[BigInt]) -> Polynomial {
+        let mut coefficients = vec![BigRational::zero(); differences.len().max(1)];
+
+        // x(x - 1)...(x - j + 1) / j!, built up one factor at a time
+        let mut basis = vec![BigRational::one()];
+
+        for (j, difference) in differences.iter().enumerate() {
+            let difference = BigRational::from_integer(difference.clone());
+            for (power, coefficient) in basis.iter().enumerate() {
+                coefficients[power] += &difference * coefficient;
+            }
+
+            // Multiply the basis by (x - j) / (j + 1)
+            let j = BigRational::from_integer(j.into());
+            let divisor = &j + BigRational::one();
+            let mut next = vec![BigRational::zero(); basis.len() + 1];
+            for (power, coefficient) in basis.iter().enumerate() {
+                next[power + 1] += coefficient / &divisor;
+                next[power] -= coefficient * &j / &divisor;
+            }
+            basis = next;
+        }
+
+        while coefficients.len() > 1 && coefficients.last().unwrap().is_zero() {
+            coefficients.pop();
+        }
+
+        Polynomial(coefficients)
+    }
+}
+
+/// Highest power first, like `(1/2)x^2 + (3/2)x + 1`.
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<(usize, &BigRational)> = self
+            .0
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .collect();
+
+        if terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        for (i, (power, coefficient)) in terms.into_iter().enumerate() {
+            let sign = match (i, coefficient.is_negative()) {
+                (0, true) => "-",
+                (0, false) => "",
+                (_, true) => " - ",
+                (_, false) => " + ",
+            };
+            let magnitude = coefficient.abs();
+
+            let coefficient = match (power, magnitude.is_one()) {
+                (0, _) => magnitude.to_string(),
+                (_, true) => String::new(),
+                (_, false) if magnitude.is_integer() => magnitude.to_string(),
+                (_, false) => format!("({magnitude})"),
+            };
+            let variable = match power {
+                0 => String::new(),
+                1 => "x".to_owned(),
+                _ => format!("x^{power}"),
+            };
+
+            write!(f, "{sign}{coefficient}{variable}")?;
+        }
+
+        Ok(())
+    }
+}
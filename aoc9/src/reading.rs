@@ -0,0 +1,93 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use num_bigint::BigInt;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Zero};
+
+/// The number types a history can be read into. Everything goes through the checked operations,
+/// so a reading type that is too small gives an error instead of a wrong answer.
+pub trait Reading: Clone + PartialEq + Debug + Display + FromStr {
+    fn zero() -> Self;
+    fn from_i64(value: i64) -> Self;
+    fn to_bigint(&self) -> BigInt;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    /// Only used where the division is known to be exact
+    fn checked_div(&self, rhs: &Self) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+macro_rules! impl_reading {
+    ($($ty:ty),*) => {
+        $(
+            impl Reading for $ty {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn from_i64(value: i64) -> Self {
+                    value.into()
+                }
+
+                fn to_bigint(&self) -> BigInt {
+                    (*self).into()
+                }
+
+                fn checked_add(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_add(*self, *rhs)
+                }
+
+                fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_sub(*self, *rhs)
+                }
+
+                fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_mul(*self, *rhs)
+                }
+
+                fn checked_div(&self, rhs: &Self) -> Option<Self> {
+                    <$ty>::checked_div(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_reading!(i64, i128);
+
+/// Never overflows, at the cost of an allocation per value.
+impl Reading for BigInt {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn from_i64(value: i64) -> Self {
+        value.into()
+    }
+
+    fn to_bigint(&self) -> BigInt {
+        self.clone()
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        CheckedAdd::checked_add(self, rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        CheckedSub::checked_sub(self, rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        CheckedMul::checked_mul(self, rhs)
+    }
+
+    fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        CheckedDiv::checked_div(self, rhs)
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::Write;
+
+use crate::history::{History, HistoryError, Shape};
+use crate::reading::Reading;
+
+/// The difference triangle for a history, laid out like the puzzle text: every row is shifted
+/// half a cell to the right of the one above it.
+pub struct Pyramid {
+    /// Every cell of every row, and whether it was extrapolated
+    rows: Vec<Vec<(String, bool)>>,
+}
+
+impl<T: Reading> History<T> {
+    /// Polynomial histories get an extrapolated cell at both ends of every row, anything else is
+    /// drawn as far as its differences go.
+    pub fn pyramid(&self) -> Result<Pyramid, HistoryError> {
+        let derivatives = self.get_all_derivatives()?;
+
+        let mut edges: Vec<Option<(T, T)>> = vec![None; derivatives.len()];
+        if let Shape::Polynomial { .. } = self.shape()? {
+            let mut below = (T::zero(), T::zero());
+
+            for (level, derivative) in derivatives.iter().enumerate().rev() {
+                let (first, last) = (&derivative.0[0], derivative.0.last().unwrap());
+                below = (
+                    first
+                        .checked_sub(&below.0)
+                        .ok_or(HistoryError::ExtrapolationOverflow)?,
+                    last.checked_add(&below.1)
+                        .ok_or(HistoryError::ExtrapolationOverflow)?,
+                );
+                edges[level] = Some(below.clone());
+            }
+        }
+
+        let rows = derivatives
+            .iter()
+            .zip(edges)
+            .map(|(derivative, edges)| {
+                let values = derivative.0.iter().map(|value| (value.to_string(), false));
+
+                match edges {
+                    Some((previous, next)) => std::iter::once((previous.to_string(), true))
+                        .chain(values)
+                        .chain(std::iter::once((next.to_string(), true)))
+                        .collect(),
+                    None => values.collect(),
+                }
+            })
+            .collect();
+
+        Ok(Pyramid { rows })
+    }
+}
+
+impl Pyramid {
+    /// Each cell takes up two grid columns, starting one column further right on every row.
+    fn grid_columns(&self) -> usize {
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(level, row)| (level + 2 * row.len()).saturating_sub(1))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Aligned text, with the extrapolated cells in bold green when `colour` is set and in
+    /// brackets otherwise, so the output still makes sense in a file.
+    pub fn to_text(&self, colour: bool) -> String {
+        let rows: Vec<Vec<(String, bool)>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|(value, extrapolated)| match (extrapolated, colour) {
+                        (true, false) => (format!("[{value}]"), true),
+                        _ => (value.clone(), *extrapolated),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let widest = rows
+            .iter()
+            .flatten()
+            .map(|(value, _)| value.len())
+            .max()
+            .unwrap_or(0);
+        // Cells are two halves wide, so every row can start half a cell further right
+        let half = widest / 2 + 1;
+
+        let mut text = String::new();
+        for (level, row) in rows.iter().enumerate() {
+            text.push_str(&" ".repeat(level * half));
+
+            for (value, extrapolated) in row {
+                text.push_str(&" ".repeat(2 * half - value.len()));
+                match extrapolated & colour {
+                    true => write!(text, "\x1b[1;32m{value}\x1b[0m").unwrap(),
+                    false => text.push_str(value),
+                }
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// A Markdown table with one column per half cell, and the extrapolated cells in bold.
+    pub fn to_markdown(&self) -> String {
+        let columns = self.grid_columns();
+        let mut markdown = String::new();
+
+        // Markdown tables need a header, so leave it empty
+        writeln!(markdown, "|{}", " |".repeat(columns)).unwrap();
+        writeln!(markdown, "|{}", "---|".repeat(columns)).unwrap();
+
+        for (level, row) in self.rows.iter().enumerate() {
+            let mut cells = vec![String::new(); columns];
+            for (i, (value, extrapolated)) in row.iter().enumerate() {
+                cells[level + 2 * i] = match extrapolated {
+                    true => format!("**{value}**"),
+                    false => value.clone(),
+                };
+            }
+            writeln!(markdown, "| {} |", cells.join(" | ")).unwrap();
+        }
+
+        markdown
+    }
+
+    /// An HTML table where every cell spans two columns, with the extrapolated cells marked.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        writeln!(html, "<table>").unwrap();
+        for (level, row) in self.rows.iter().enumerate() {
+            write!(html, "  <tr>").unwrap();
+            if level > 0 {
+                write!(html, r#"<td colspan="{level}"></td>"#).unwrap();
+            }
+            for (value, extrapolated) in row {
+                match extrapolated {
+                    true => write!(html, r#"<td colspan="2"><mark>{value}</mark></td>"#).unwrap(),
+                    false => write!(html, r#"<td colspan="2">{value}</td>"#).unwrap(),
+                }
+            }
+            writeln!(html, "</tr>").unwrap();
+        }
+        writeln!(html, "</table>").unwrap();
+
+        html
+    }
+}